@@ -0,0 +1,52 @@
+use memorizer::algorithm::memorize::recall_curve::RecallCurveConfig;
+use memorizer::optimize::{fit_recall_curve, recall_curve_log_likelihood, records_by_question};
+use memorizer::recorder::YamlRecorder;
+use memorizer::traits::Recorder;
+
+use clap::Parser;
+
+/// Fit the recall curve selector configuration to the records in a log file.
+#[derive(Parser, Debug)]
+#[clap(long_about = None)]
+struct Args {
+    /// The yaml log file to read records from.
+    log_file: String,
+
+    /// Configuration to start the fit from, defaults are used if not provided.
+    #[clap(long)]
+    initial: Option<String>,
+
+    /// The file to write the fitted configuration to.
+    #[clap(short, long)]
+    output: String,
+}
+
+fn main() -> Result<(), memorizer::traits::MemorizerError> {
+    let args = Args::parse();
+    let recorder = YamlRecorder::new(&std::path::PathBuf::from(&args.log_file))?;
+    let records = recorder.get_records()?;
+
+    let initial: RecallCurveConfig = if let Some(initial) = args.initial.as_ref() {
+        let file =
+            std::fs::File::open(initial).map_err(|e| format!("failed to open {initial}: {e:?}"))?;
+        serde_yaml::from_reader(file)?
+    } else {
+        Default::default()
+    };
+
+    let fitted = fit_recall_curve(&records, &initial);
+
+    let grouped = records_by_question(&records);
+    println!(
+        "Log likelihood of {} records: {} -> {}",
+        records.len(),
+        recall_curve_log_likelihood(&grouped, &initial),
+        recall_curve_log_likelihood(&grouped, &fitted)
+    );
+    println!("{fitted:#?}");
+
+    let file = std::fs::File::create(&args.output)
+        .map_err(|e| format!("failed to write to {}: {e:?}", args.output))?;
+    serde_yaml::to_writer(file, &fitted)?;
+    Ok(())
+}
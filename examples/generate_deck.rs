@@ -62,10 +62,9 @@ fn read_learnables_from_txt(
             .map(|v| v.to_owned())
             .collect::<Vec<String>>();
         if entries.len() < 2 {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to find two entries in : {line}"),
-            )));
+            return Err(Box::new(std::io::Error::other(format!(
+                "Failed to find two entries in : {line}"
+            ))));
         }
         let mut edges = vec![];
         let t1 = TextRepresentation::new(&entries[0], RepresentationId(str_to_hash(&entries[0])));
@@ -164,7 +163,7 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
     for input in args.inputs.iter() {
         if input.ends_with("txt") {
             learnables.extend(read_learnables_from_txt(
-                &input,
+                input,
                 include_reverse,
                 &transform_to,
                 &transform_reverse,
@@ -173,7 +172,7 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
 
         if input.ends_with("yaml") || input.ends_with("yml") {
             learnables.extend(read_learnables_from_yaml(
-                &input,
+                input,
                 include_reverse,
                 &transform_to,
                 &transform_reverse,
@@ -181,6 +180,6 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
         }
     }
 
-    save_text_learnables(&args.output, &format!("some example name."), &learnables)?;
+    save_text_learnables(&args.output, "some example name.", &learnables)?;
     Ok(())
 }
@@ -46,7 +46,7 @@ fn parse_number(v: &str) -> u64 {
         return u64::from_str_radix(v.trim_start_matches("0b"), 2).expect("unable to parse binary");
     }
 
-    return u64::from_str(v).expect("unable to parse binary");
+    u64::from_str(v).expect("unable to parse binary")
 }
 
 /// Convert a string of number specifications.
@@ -64,13 +64,12 @@ fn parse_number_spec(v: &str) -> Vec<u64> {
             .expect("Should've had a start of the range");
         let end_string = start_end.next().expect("Should've had a end of the range");
 
-        let start = parse_number(&start);
-        let end: u64;
-        if end_string.starts_with("=") {
-            end = parse_number(&end_string[1..]) + 1;
+        let start = parse_number(start);
+        let end = if let Some(inclusive_end) = end_string.strip_prefix('=') {
+            parse_number(inclusive_end) + 1
         } else {
-            end = parse_number(&end_string);
-        }
+            parse_number(end_string)
+        };
         for i in start..end {
             res.push(i);
         }
@@ -207,13 +206,13 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
         if let Some(v) = unprintables.get(&value) {
             return TextRepresentation::new(v, RepresentationId(value + ASCII_SHIFT));
         }
-        return TextRepresentation::new(
+        TextRepresentation::new(
             &format!(
                 "{}",
                 std::char::from_u32(value as u32).expect("Should be valid ascii")
             ),
             RepresentationId(value + ASCII_SHIFT),
-        );
+        )
     }
     fn valid_ascii(value: u64) -> bool {
         // Ok... so this is a bit tricky.
@@ -235,74 +234,50 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
     let numbers = parse_number_spec(&args.number_spec);
 
     for i in numbers {
-        let v = i as u64;
+        let v = i;
         let mut edges = vec![];
         for direction in args.directions.iter() {
             match direction {
                 m if m == &Direction::BinDec => {
+                    edges.push((make_bin(v), transforms.get(m).unwrap().clone(), make_dec(v)));
+                }
+                m if m == &Direction::DecBin => {
+                    edges.push((make_dec(v), transforms.get(m).unwrap().clone(), make_bin(v)));
+                }
+                m if m == &Direction::HexDec => {
+                    edges.push((make_hex(v), transforms.get(m).unwrap().clone(), make_dec(v)));
+                }
+                m if m == &Direction::DecHex => {
+                    edges.push((make_dec(v), transforms.get(m).unwrap().clone(), make_hex(v)));
+                }
+                m if m == &Direction::AsciiDec && valid_ascii(v) => {
                     edges.push((
-                        make_bin(v),
-                        transforms.get(&m).unwrap().clone(),
+                        make_ascii(v),
+                        transforms.get(m).unwrap().clone(),
                         make_dec(v),
                     ));
                 }
-                m if m == &Direction::DecBin => {
+                m if m == &Direction::DecAscii && valid_ascii(v) => {
                     edges.push((
                         make_dec(v),
-                        transforms.get(&m).unwrap().clone(),
-                        make_bin(v),
+                        transforms.get(m).unwrap().clone(),
+                        make_ascii(v),
                     ));
                 }
-                m if m == &Direction::HexDec => {
+                m if m == &Direction::AsciiHex && valid_ascii(v) => {
                     edges.push((
+                        make_ascii(v),
+                        transforms.get(m).unwrap().clone(),
                         make_hex(v),
-                        transforms.get(&m).unwrap().clone(),
-                        make_dec(v),
                     ));
                 }
-                m if m == &Direction::DecHex => {
+                m if m == &Direction::HexAscii && valid_ascii(v) => {
                     edges.push((
-                        make_dec(v),
-                        transforms.get(&m).unwrap().clone(),
                         make_hex(v),
+                        transforms.get(m).unwrap().clone(),
+                        make_ascii(v),
                     ));
                 }
-                m if m == &Direction::AsciiDec => {
-                    if valid_ascii(v) {
-                        edges.push((
-                            make_ascii(v),
-                            transforms.get(&m).unwrap().clone(),
-                            make_dec(v),
-                        ));
-                    }
-                }
-                m if m == &Direction::DecAscii => {
-                    if valid_ascii(v) {
-                        edges.push((
-                            make_dec(v),
-                            transforms.get(&m).unwrap().clone(),
-                            make_ascii(v),
-                        ));
-                    }
-                }
-                m if m == &Direction::AsciiHex => {
-                    if valid_ascii(v) {
-                        edges.push((
-                            make_ascii(v),
-                            transforms.get(&m).unwrap().clone(),
-                            make_hex(v),
-                        ));
-                    }
-                }
-                m if m == &Direction::HexAscii => {
-                    if valid_ascii(v) {
-                        edges.push((
-                            make_hex(v),
-                            transforms.get(&m).unwrap().clone(),
-                            make_ascii(v),
-                        ));
-                    }
-                }
                 _ => {}
             }
         }
@@ -1,7 +1,7 @@
 use memorizer::recorder::YamlRecorder;
 use memorizer::text::load_text_learnables;
 use memorizer::training::Training;
-use memorizer::traits::{LearnableId, Record, Selector};
+use memorizer::traits::{Explanation, LearnableId, Progress, Record, Selector};
 
 use std::sync::Arc;
 use std::thread;
@@ -46,19 +46,137 @@ fn file_to_response(path: &std::path::Path, file: std::fs::File) -> Response<std
 #[derive(Deserialize, Serialize, Debug)]
 enum SelectorOptions {
     SuperMemo2,
+    /// SuperMemo2 selector with limits on the new questions and reviews.
+    SuperMemo2Config(memorizer::algorithm::super_memo_2::SuperMemo2Config),
     RecallCurveSelector,
+    /// Recall curve selector with the configuration file written by the fit_selector example.
+    RecallCurveSelectorConfig(String),
+    Fsrs,
+    Leitner,
+    HalfLifeRegression,
+    Ebisu,
+    Memorize,
+    /// Another selector, holding back the other questions of a learnable after one is answered.
+    SiblingBury {
+        selector: Box<SelectorOptions>,
+        #[serde(default)]
+        config: memorizer::algorithm::sibling_bury::SiblingBuryConfig,
+    },
+    /// Another selector, with partial credit for the questions related to the one answered.
+    Transfer {
+        selector: Box<SelectorOptions>,
+        #[serde(default)]
+        config: memorizer::algorithm::transfer::TransferConfig,
+    },
+    /// Another selector, tracking the questions that keep being failed and optionally suspending
+    /// them.
+    Leech {
+        selector: Box<SelectorOptions>,
+        #[serde(default)]
+        config: memorizer::algorithm::leech::LeechConfig,
+    },
+    /// Another selector, only asking the questions that match the filter.
+    Filter {
+        selector: Box<SelectorOptions>,
+        filter: memorizer::algorithm::combinators::QuestionFilter,
+    },
+    /// The primary selector, once that has no more questions the fallback selector.
+    Fallback {
+        primary: Box<SelectorOptions>,
+        fallback: Box<SelectorOptions>,
+    },
+    /// Interleave several selectors, picking from them at random by weight.
+    Weighted(Vec<WeightedSelectorOptions>),
 }
+
+#[derive(Deserialize, Serialize, Debug)]
+struct WeightedSelectorOptions {
+    weight: f64,
+    selector: SelectorOptions,
+}
+
 impl SelectorOptions {
-    pub fn make_selector(&self) -> Box<dyn Selector> {
-        match *self {
-            SelectorOptions::SuperMemo2 => {
-                Box::new(memorizer::algorithm::super_memo_2::SuperMemo2Selector::new())
-            }
+    pub fn make_selector(&self) -> Result<Box<dyn Selector>, BackendError> {
+        use memorizer::algorithm::memorize::recall_curve::RecallCurveSelector;
+        Ok(match self {
+            SelectorOptions::SuperMemo2 => Box::new(
+                memorizer::algorithm::super_memo_2::SuperMemo2Selector::new(Default::default()),
+            ),
+            SelectorOptions::SuperMemo2Config(config) => Box::new(
+                memorizer::algorithm::super_memo_2::SuperMemo2Selector::new(config.clone()),
+            ),
             SelectorOptions::RecallCurveSelector => {
-                use memorizer::algorithm::memorize::recall_curve::RecallCurveSelector;
                 Box::new(RecallCurveSelector::new(Default::default()))
             }
-        }
+            SelectorOptions::RecallCurveSelectorConfig(path) => {
+                let file = std::fs::File::open(path)
+                    .map_err(|e| format!("failed to open {path}: {e:?}"))?;
+                Box::new(RecallCurveSelector::new(serde_yaml::from_reader(file)?))
+            }
+            SelectorOptions::Fsrs => {
+                use memorizer::algorithm::fsrs::FsrsSelector;
+                Box::new(FsrsSelector::new(Default::default()))
+            }
+            SelectorOptions::Leitner => {
+                use memorizer::algorithm::leitner::LeitnerSelector;
+                Box::new(LeitnerSelector::new(Default::default())?)
+            }
+            SelectorOptions::HalfLifeRegression => {
+                use memorizer::algorithm::half_life_regression::HalfLifeRegressionSelector;
+                Box::new(HalfLifeRegressionSelector::new(Default::default()))
+            }
+            SelectorOptions::Ebisu => {
+                use memorizer::algorithm::ebisu::EbisuSelector;
+                Box::new(EbisuSelector::new(Default::default()))
+            }
+            SelectorOptions::Memorize => {
+                use memorizer::algorithm::memorize::scheduler::MemorizeSelector;
+                Box::new(MemorizeSelector::new(Default::default()))
+            }
+            SelectorOptions::SiblingBury { selector, config } => {
+                use memorizer::algorithm::sibling_bury::SiblingBurySelector;
+                Box::new(SiblingBurySelector::new(
+                    selector.make_selector()?,
+                    config.clone(),
+                ))
+            }
+            SelectorOptions::Leech { selector, config } => {
+                use memorizer::algorithm::leech::LeechSelector;
+                Box::new(LeechSelector::new(
+                    selector.make_selector()?,
+                    config.clone(),
+                ))
+            }
+            SelectorOptions::Transfer { selector, config } => {
+                use memorizer::algorithm::transfer::TransferSelector;
+                Box::new(TransferSelector::new(
+                    selector.make_selector()?,
+                    config.clone(),
+                ))
+            }
+            SelectorOptions::Filter { selector, filter } => {
+                use memorizer::algorithm::combinators::FilterSelector;
+                Box::new(FilterSelector::from_filter(
+                    selector.make_selector()?,
+                    filter.clone(),
+                ))
+            }
+            SelectorOptions::Fallback { primary, fallback } => {
+                use memorizer::algorithm::combinators::FallbackSelector;
+                Box::new(FallbackSelector::new(
+                    primary.make_selector()?,
+                    fallback.make_selector()?,
+                ))
+            }
+            SelectorOptions::Weighted(options) => {
+                use memorizer::algorithm::combinators::WeightedSelector;
+                let mut selectors = vec![];
+                for option in options.iter() {
+                    selectors.push((option.weight, option.selector.make_selector()?));
+                }
+                Box::new(WeightedSelector::new(selectors))
+            }
+        })
     }
 }
 
@@ -81,6 +199,9 @@ struct NamedDeck {
 struct UserDecks {
     username: UserName,
     decks: Vec<NamedDeck>,
+    /// Timezone and time at which the user's day starts, for the intervals in days.
+    #[serde(default)]
+    day_boundary: memorizer::clock::DayBoundary,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -98,14 +219,33 @@ type UserTraining = std::collections::HashMap<DeckName, ThreadsafeTraining>;
 #[derive(Default)]
 struct TrainingBackend {
     entries: std::collections::HashMap<UserName, UserTraining>,
+    /// Decks that were answered since their last snapshot, see store_snapshots.
+    unsnapshotted: parking_lot::Mutex<std::collections::HashSet<(UserName, DeckName)>>,
 }
 
+/// How often the snapshots of answered decks are stored.
+const SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 struct FullTextQuestion {
     from: String,
     transform: String,
     to: String,
     learnable: LearnableId,
+    /// Predicted probability of recall, if the selector provides it.
+    #[serde(default)]
+    predicted_recall: Option<f64>,
+}
+
+/// How to hold back a question from being asked.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+enum SuspendAction {
+    /// Until it is unsuspended.
+    Suspend,
+    /// Until the next day.
+    Bury,
+    /// Undo a suspension or burial.
+    Unsuspend,
 }
 
 impl TrainingBackend {
@@ -114,7 +254,7 @@ impl TrainingBackend {
     }
     pub fn decks(&self, user: &UserName) -> Vec<DeckName> {
         if let Some(user_decks) = self.entries.get(user) {
-            user_decks.keys().into_iter().cloned().collect()
+            user_decks.keys().cloned().collect()
         } else {
             vec![]
         }
@@ -126,7 +266,7 @@ impl TrainingBackend {
         deck: &DeckName,
     ) -> Result<Option<FullTextQuestion>, BackendError> {
         let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
-        let deck = users_decks.get(deck).ok_or(format!("no user {deck:?}"))?;
+        let deck = users_decks.get(deck).ok_or(format!("no deck {deck:?}"))?;
         let mut deck = deck.write();
         if let Some(v) = deck.question() {
             let answer_repr = deck.get_answer(&v)?;
@@ -137,12 +277,90 @@ impl TrainingBackend {
                 transform: transform.description().to_owned(),
                 to: answer_repr.text().to_owned(),
                 learnable: v.learnable,
+                predicted_recall: deck.predict_recall(&v),
             }))
         } else {
             Ok(None)
         }
     }
 
+    pub fn forecast(
+        &self,
+        user: &UserName,
+        deck: &DeckName,
+        days: usize,
+    ) -> Result<Option<Vec<usize>>, BackendError> {
+        let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
+        let deck = users_decks.get(deck).ok_or(format!("no deck {deck:?}"))?;
+        let deck = deck.read();
+        Ok(deck.forecast(days))
+    }
+
+    pub fn progress(
+        &self,
+        user: &UserName,
+        deck: &DeckName,
+    ) -> Result<Option<Progress>, BackendError> {
+        let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
+        let deck = users_decks.get(deck).ok_or(format!("no deck {deck:?}"))?;
+        let deck = deck.read();
+        Ok(deck.progress())
+    }
+
+    /// Why the last question of this deck was chosen, for debugging the scheduling.
+    pub fn explain(
+        &self,
+        user: &UserName,
+        deck: &DeckName,
+    ) -> Result<Option<Explanation>, BackendError> {
+        let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
+        let deck = users_decks.get(deck).ok_or(format!("no deck {deck:?}"))?;
+        let deck = deck.read();
+        Ok(deck.explain())
+    }
+
+    pub fn set_cram(
+        &self,
+        user: &UserName,
+        deck: &DeckName,
+        enabled: bool,
+    ) -> Result<(), BackendError> {
+        let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
+        let deck = users_decks.get(deck).ok_or(format!("no deck {deck:?}"))?;
+        let mut deck = deck.write();
+        deck.set_cram(enabled);
+        Ok(())
+    }
+
+    pub fn suspend_question(
+        &self,
+        user: &UserName,
+        deck_name: &DeckName,
+        learnable: LearnableId,
+        action: SuspendAction,
+    ) -> Result<(), BackendError> {
+        let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
+        let deck = users_decks
+            .get(deck_name)
+            .ok_or(format!("no deck {deck_name:?}"))?;
+        let mut deck = deck.write();
+        // The interface only knows the learnable, act on all of its questions.
+        let questions = deck.questions_from_learnable(learnable);
+        if questions.is_empty() {
+            return Err(
+                format!("could not find {learnable:?} in deck {deck_name:?} for {user:?}").into(),
+            );
+        }
+        for question in questions.iter() {
+            match action {
+                SuspendAction::Suspend => deck.suspend(question)?,
+                SuspendAction::Bury => deck.bury(question)?,
+                SuspendAction::Unsuspend => deck.unsuspend(question)?,
+            }
+        }
+        Ok(())
+    }
+
     pub fn rate_question(
         &self,
         user: &UserName,
@@ -153,15 +371,18 @@ impl TrainingBackend {
         let users_decks = self.entries.get(user).ok_or(format!("no user {user:?}"))?;
         let deck = users_decks
             .get(deck_name)
-            .ok_or(format!("no user {deck_name:?}"))?;
+            .ok_or(format!("no deck {deck_name:?}"))?;
         let mut deck = deck.write();
         if let Some(question) = deck.question_from_learnable(learnable) {
             let record = Record {
-                question: question,
+                question,
                 score: score.clamp(0.0, 1.0),
-                time: std::time::SystemTime::now(),
+                time: deck.now(),
             };
             deck.finalize_answer(record)?;
+            self.unsnapshotted
+                .lock()
+                .insert((user.clone(), deck_name.clone()));
             Ok(())
         } else {
             Err(format!("could not find {learnable:?} in deck {deck_name:?} for {user:?}").into())
@@ -170,6 +391,21 @@ impl TrainingBackend {
 }
 
 impl TrainingBackend {
+    /// Store the snapshots of the decks that were answered since their last snapshot, such that
+    /// a restart doesn't replay all records. The answers are stored already, a failure here only
+    /// makes the next start slower.
+    pub fn store_snapshots(&self) {
+        let unsnapshotted = std::mem::take(&mut *self.unsnapshotted.lock());
+        for (user, deck_name) in unsnapshotted {
+            let Some(deck) = self.entries.get(&user).and_then(|d| d.get(&deck_name)) else {
+                continue;
+            };
+            if let Err(e) = deck.write().store_snapshot() {
+                eprintln!("failed to store snapshot of {deck_name:?} for {user:?}: {e}");
+            }
+        }
+    }
+
     pub fn from_config(config: &HostConfig, storage_dir: &str) -> Result<Self, BackendError> {
         let mut res = TrainingBackend::default();
         let storage_dir = PathBuf::from(storage_dir);
@@ -180,7 +416,7 @@ impl TrainingBackend {
                     .selector
                     .as_ref()
                     .unwrap_or(&config.selector)
-                    .make_selector();
+                    .make_selector()?;
                 // Load the actual deck.
                 let deck_learnables = load_text_learnables(&deck.path)?;
 
@@ -188,7 +424,12 @@ impl TrainingBackend {
                     .join(&user_deck.username.0)
                     .join(format!("{}_recording.yaml", deck.name.0));
                 let recorder = YamlRecorder::new(&recorder_file_path)?;
-                let training = Training::new(deck_learnables, Box::new(recorder), selector);
+                let training = Training::with_day_boundary(
+                    deck_learnables,
+                    Box::new(recorder),
+                    selector,
+                    user_deck.day_boundary,
+                )?;
                 user_map.insert(deck.name.clone(), training.into());
             }
         }
@@ -226,19 +467,17 @@ impl Hoster {
         if !path.is_file() {
             return Ok(None);
         }
-        let file = fs::File::open(&path);
-
-        if file.is_ok() {
-            return Ok(Some(file_to_response(&path, file.unwrap()).boxed()));
+        if let Ok(file) = fs::File::open(&path) {
+            Ok(Some(file_to_response(&path, file).boxed()))
         } else {
-            return Err("could not open file".into());
+            Err("could not open file".into())
         }
     }
 
     pub fn request_file(&self, rq: &Request) -> Result<Option<ResponseBox>, BackendError> {
         let url = rq.url().to_string();
         let path = url.strip_prefix("/").unwrap();
-        let path = if path == "" { "index.html" } else { path };
+        let path = if path.is_empty() { "index.html" } else { path };
         self.serve_file(Path::new(&path))
     }
 
@@ -306,8 +545,8 @@ impl Hoster {
             full_path if path.starts_with("api/question/") => {
                 let query = full_path.replace("api/question/", "");
                 let mut elements = query.split("/");
-                let user = elements.next().ok_or(format!("no user provided"))?;
-                let deck = elements.next().ok_or(format!("no deck provided"))?;
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
 
                 let user = UserName(user.to_owned());
                 let deck = DeckName(deck.to_owned());
@@ -319,11 +558,119 @@ impl Hoster {
                         .boxed(),
                 ))
             }
+            full_path if path.starts_with("api/forecast/") => {
+                let query = full_path.replace("api/forecast/", "");
+                let mut elements = query.split("/");
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
+
+                let user = UserName(user.to_owned());
+                let deck = DeckName(deck.to_owned());
+
+                /// Questions due per day, the first day is the questions that are due now.
+                #[derive(Debug, Clone, Serialize, Deserialize)]
+                struct ForecastResponse {
+                    days: Vec<usize>,
+                }
+                const FORECAST_DAYS: usize = 8;
+                let resp = self
+                    .backend
+                    .forecast(&user, &deck, FORECAST_DAYS)?
+                    .map(|days| ForecastResponse { days });
+                Ok(Some(
+                    tiny_http::Response::from_string(serde_json::to_string_pretty(&resp).unwrap())
+                        .with_status_code(tiny_http::StatusCode(200))
+                        .boxed(),
+                ))
+            }
+            full_path if path.starts_with("api/progress/") => {
+                let query = full_path.replace("api/progress/", "");
+                let mut elements = query.split("/");
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
+
+                let user = UserName(user.to_owned());
+                let deck = DeckName(deck.to_owned());
+
+                let resp = self.backend.progress(&user, &deck)?;
+                Ok(Some(
+                    tiny_http::Response::from_string(serde_json::to_string_pretty(&resp).unwrap())
+                        .with_status_code(tiny_http::StatusCode(200))
+                        .boxed(),
+                ))
+            }
+            full_path if path.starts_with("api/explain/") => {
+                let query = full_path.replace("api/explain/", "");
+                let mut elements = query.split("/");
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
+
+                let user = UserName(user.to_owned());
+                let deck = DeckName(deck.to_owned());
+
+                let resp = self.backend.explain(&user, &deck)?;
+                Ok(Some(
+                    tiny_http::Response::from_string(serde_json::to_string_pretty(&resp).unwrap())
+                        .with_status_code(tiny_http::StatusCode(200))
+                        .boxed(),
+                ))
+            }
+            full_path if path.starts_with("api/cram/") => {
+                let query = full_path.replace("api/cram/", "");
+                let mut elements = query.split("/");
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
+
+                let user = UserName(user.to_owned());
+                let deck = DeckName(deck.to_owned());
+
+                /// Enables or disables cram mode, answers are not recorded while cramming.
+                #[derive(Debug, Clone, Serialize, Deserialize)]
+                struct CramRequest {
+                    enabled: bool,
+                }
+
+                let mut content = String::new();
+                rq.as_reader().read_to_string(&mut content)?;
+                let cram: CramRequest = serde_json::from_str(&content)?;
+                self.backend.set_cram(&user, &deck, cram.enabled)?;
+                Ok(Some(
+                    tiny_http::Response::from_string("{\"response\":\"stored\"}")
+                        .with_status_code(tiny_http::StatusCode(200))
+                        .boxed(),
+                ))
+            }
+            full_path if path.starts_with("api/suspend/") => {
+                let query = full_path.replace("api/suspend/", "");
+                let mut elements = query.split("/");
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
+
+                let user = UserName(user.to_owned());
+                let deck = DeckName(deck.to_owned());
+
+                #[derive(Debug, Clone, Serialize, Deserialize)]
+                struct SuspendRequest {
+                    learnable: LearnableId,
+                    action: SuspendAction,
+                }
+
+                let mut content = String::new();
+                rq.as_reader().read_to_string(&mut content)?;
+                let suspend: SuspendRequest = serde_json::from_str(&content)?;
+                self.backend
+                    .suspend_question(&user, &deck, suspend.learnable, suspend.action)?;
+                Ok(Some(
+                    tiny_http::Response::from_string("{\"response\":\"stored\"}")
+                        .with_status_code(tiny_http::StatusCode(200))
+                        .boxed(),
+                ))
+            }
             full_path if path.starts_with("api/submit_answer/") => {
                 let query = full_path.replace("api/submit_answer/", "");
                 let mut elements = query.split("/");
-                let user = elements.next().ok_or(format!("no user provided"))?;
-                let deck = elements.next().ok_or(format!("no deck provided"))?;
+                let user = elements.next().ok_or("no user provided")?;
+                let deck = elements.next().ok_or("no deck provided")?;
 
                 let user = UserName(user.to_owned());
                 let deck = DeckName(deck.to_owned());
@@ -355,7 +702,7 @@ impl Hoster {
             _ if path.starts_with("?") => {
                 // handle anything starting with ? by the index.html, this allows it to behave as a
                 // single page application while still taking state from the url.
-                return self.serve_file(&std::path::PathBuf::from("index.html"));
+                self.serve_file(&std::path::PathBuf::from("index.html"))
             }
             _ => Ok(None),
         }
@@ -407,6 +754,16 @@ pub fn main() -> Result<(), BackendError> {
 
     let backend = Arc::new(Hoster::new(&args.www, training_backend)?);
 
+    // Snapshots are written periodically rather than after every answer, writing one is about
+    // as expensive as the whole deck.
+    {
+        let backend = backend.clone();
+        thread::spawn(move || loop {
+            thread::sleep(SNAPSHOT_INTERVAL);
+            backend.backend.store_snapshots();
+        });
+    }
+
     let mut handles = Vec::new();
     for _ in 0..args.threads {
         let server = server.clone();
@@ -432,7 +789,7 @@ pub fn main() -> Result<(), BackendError> {
                 for t in order {
                     let z = t(&mut rq);
                     let served = if z.is_ok() {
-                        z.as_ref().ok().unwrap().is_some() == true
+                        z.as_ref().ok().unwrap().is_some()
                     } else {
                         false
                     };
@@ -443,17 +800,13 @@ pub fn main() -> Result<(), BackendError> {
                     }
                 }
 
-                if !r
-                    .iter()
-                    .map(|v| {
-                        if v.is_ok() {
-                            v.as_ref().ok().unwrap().is_some()
-                        } else {
-                            true // error, it was definitely handled, but something went bad.
-                        }
-                    })
-                    .any(|v| v)
-                {
+                if !r.iter().any(|v| {
+                    if v.is_ok() {
+                        v.as_ref().ok().unwrap().is_some()
+                    } else {
+                        true // error, it was definitely handled, but something went bad.
+                    }
+                }) {
                     let rep = tiny_http::Response::from_string("Nothing handles this request")
                         .with_status_code(tiny_http::StatusCode(500));
                     let _ = rq.respond(rep);
@@ -46,6 +46,15 @@ class Memorizer {
             link.classList.add("buttondiv");
             link.classList.add("stackedbutton");
             r.push(link);
+            self.add_deck_forecast(link, deck_name);
+
+            let cram = document.createElement("a");
+            cram.text = `cram ${deck_name}`;
+            cram.href = `?user=${self.user}&deck=${deck_name}&cram=1`;
+            cram.classList.add("buttondiv");
+            cram.classList.add("stackedbutton");
+            cram.classList.add("crambutton");
+            r.push(cram);
             
             console.log("deck_name", deck_name);
           }
@@ -57,13 +66,42 @@ class Memorizer {
       .catch((error) => console.log("Something went wrong in the request: ", error));
   }
 
-  enter_training(deck) {
+  add_deck_forecast(link, deck_name) {
+    fetch(`/api/forecast/${this.user}/${deck_name}`)
+        .then((response) => response.json())
+        .then(function(data) {
+          if (data == null) {
+            return;
+          }
+          let week = data.days.slice(1).reduce((a, b) => a + b, 0);
+          let forecast = document.createElement("span");
+          forecast.classList.add("deck_forecast");
+          forecast.textContent = `now: ${data.days[0]}, tomorrow: ${data.days[1]}, in a week: ${week}`;
+          link.appendChild(forecast);
+        })
+      .catch((error) => console.log("Something went wrong in the request: ", error));
+  }
+
+  enter_training(deck, cram) {
     document.getElementById("deck_select").classList.add("hidden");
     let self = this;
     self.deck = deck;
-    console.log("deck name", deck, "state", self.training_state);
-    self.training_state = TrainingState.ObtainingQuestion;
-    self.redraw_training();
+    console.log("deck name", deck, "cram", cram, "state", self.training_state);
+    if (cram) {
+      document.getElementById("training_cram").classList.remove("hidden");
+    }
+    // Cram mode is kept per deck in the backend, always set it such that a normal session
+    // doesn't continue cramming.
+    fetch(`/api/cram/${this.user}/${this.deck}`, {
+        method: "POST",
+        body: JSON.stringify({enabled: cram}),
+      })
+        .then((response) => response.json())
+        .then(function(data) {
+          self.training_state = TrainingState.ObtainingQuestion;
+          self.redraw_training();
+        })
+      .catch((error) => console.log("Something went wrong in the request: ", error));
   }
 
   redraw_training() {
@@ -84,6 +122,7 @@ class Memorizer {
 
         document.getElementById("training_rate_submit").classList.add("hidden");
         document.getElementById("training_retrieving").classList.remove("hidden");
+        self.update_progress();
         fetch(`/api/question/${this.user}/${this.deck}`)
             .then((response) => response.json())
             .then(function(data) {
@@ -104,6 +143,12 @@ class Memorizer {
         document.getElementById("training_retrieving").classList.add("hidden");
         document.getElementById("training_ask").classList.remove("hidden");
         document.getElementById("training_question_text").innerHTML = renderHtml(self.training_question.from);
+        if (self.training_question.predicted_recall != null) {
+          let recall = Math.round(self.training_question.predicted_recall * 100);
+          document.getElementById("training_question_recall").textContent = `Predicted recall: ${recall}%`;
+        } else {
+          document.getElementById("training_question_recall").textContent = "";
+        }
         document.getElementById("training_question_answer").focus();
 
 
@@ -132,6 +177,25 @@ class Memorizer {
     }
   }
 
+  update_progress() {
+    fetch(`/api/progress/${this.user}/${this.deck}`)
+        .then((response) => response.json())
+        .then(function(data) {
+          let progress = document.getElementById("training_progress");
+          if (data == null) {
+            progress.classList.add("hidden");
+            return;
+          }
+          let done = data.total - data.due - data.re_review - data.new;
+          document.getElementById("training_progress_bar").max = data.total;
+          document.getElementById("training_progress_bar").value = done;
+          document.getElementById("training_progress_text").textContent =
+            `${data.due} due, ${data.re_review} in re-review, ${data.new} not yet started, ${data.mature} mature`;
+          progress.classList.remove("hidden");
+        })
+      .catch((error) => console.log("Something went wrong in the request: ", error));
+  }
+
   training_answer_submit(e) {
     let self = this;
 
@@ -218,9 +282,41 @@ class Memorizer {
     
   }
 
+  training_suspend(e, action) {
+    let self = this;
+
+    if (self.interaction_disabled) {
+      return;
+    }
+
+    if (e != undefined) {
+      e.preventDefault();
+    }
+
+    console.log("holding back ", this.training_question, " with ", action);
+    document.getElementById("training_ask").classList.add("hidden");
+    let payload = {
+      learnable: this.training_question.learnable,
+      action: action,
+    };
+    fetch(`/api/suspend/${self.user}/${self.deck}`, {
+        method: "POST",
+        body: JSON.stringify(payload),
+      })
+        .then((response) => response.json())
+        .then(function(data) {
+          self.training_state = TrainingState.ObtainingQuestion;
+          self.redraw_training();
+        })
+      .catch((error) => console.log("Something went wrong in the request: ", error));
+  }
+
   register_inputs() {
     let self = this;
 
+    document.getElementById("training_bury").addEventListener("click", (e) => { self.training_suspend(e, "Bury"); });
+    document.getElementById("training_suspend").addEventListener("click", (e) => { self.training_suspend(e, "Suspend"); });
+
     document.getElementById("training_answer_submit").addEventListener("click", (e) => {
       self.training_answer_submit(e);
     });
@@ -269,7 +365,7 @@ function main_setup() {
   console.log("using user: ", user);
 
   if (params.has("deck")) {
-    memorizer.enter_training(params.get("deck"));
+    memorizer.enter_training(params.get("deck"), params.has("cram"));
   } else {
     // Deck select.
     memorizer.view_deck_select();
@@ -0,0 +1,131 @@
+use memorizer::algorithm::ebisu::{EbisuConfig, EbisuSelector};
+use memorizer::algorithm::fsrs::{FsrsConfig, FsrsSelector};
+use memorizer::algorithm::half_life_regression::{
+    HalfLifeRegressionConfig, HalfLifeRegressionSelector,
+};
+use memorizer::algorithm::leitner::{LeitnerConfig, LeitnerSelector};
+use memorizer::algorithm::memorize::recall_curve::{RecallCurveConfig, RecallCurveSelector};
+use memorizer::algorithm::memorize::scheduler::{MemorizeConfig, MemorizeSelector};
+use memorizer::algorithm::super_memo_2::{SuperMemo2Config, SuperMemo2Selector};
+use memorizer::simulation::{simulate, SimulationConfig, SimulationReport};
+use memorizer::text::load_text_learnables;
+use memorizer::traits::Selector;
+
+use clap::{Parser, ValueEnum};
+
+#[derive(Debug, ValueEnum, Clone, Eq, PartialEq, Hash)]
+enum SelectorArg {
+    SuperMemo2,
+    RecallCurveSelector,
+    Fsrs,
+    Leitner,
+    HalfLifeRegression,
+    Ebisu,
+    Memorize,
+}
+
+/// Simulate a learner on decks with several selectors, writes a report to compare them.
+#[derive(Parser, Debug)]
+#[clap(long_about = None)]
+struct Args {
+    /// The selectors to simulate.
+    #[clap(value_enum, long, required = true)]
+    selector: Vec<SelectorArg>,
+
+    /// Simulation configuration file, defaults are used if not provided.
+    #[clap(long)]
+    config: Option<String>,
+
+    /// Overrides the number of days in the simulation configuration.
+    #[clap(long)]
+    days: Option<usize>,
+
+    /// Overrides the seed in the simulation configuration.
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// The file to write the yaml report to.
+    #[clap(short, long)]
+    output: String,
+
+    /// The yaml files with learnables to load.
+    #[clap(required = true)]
+    learnables: Vec<String>,
+}
+
+fn make_selector(
+    selector: &SelectorArg,
+) -> Result<Box<dyn Selector>, memorizer::traits::MemorizerError> {
+    Ok(match selector {
+        SelectorArg::SuperMemo2 => {
+            let config: SuperMemo2Config = Default::default();
+            Box::new(SuperMemo2Selector::new(config))
+        }
+        SelectorArg::RecallCurveSelector => {
+            let config: RecallCurveConfig = Default::default();
+            Box::new(RecallCurveSelector::new(config))
+        }
+        SelectorArg::Fsrs => {
+            let config: FsrsConfig = Default::default();
+            Box::new(FsrsSelector::new(config))
+        }
+        SelectorArg::Leitner => {
+            let config: LeitnerConfig = Default::default();
+            Box::new(LeitnerSelector::new(config)?)
+        }
+        SelectorArg::HalfLifeRegression => {
+            let config: HalfLifeRegressionConfig = Default::default();
+            Box::new(HalfLifeRegressionSelector::new(config))
+        }
+        SelectorArg::Ebisu => {
+            let config: EbisuConfig = Default::default();
+            Box::new(EbisuSelector::new(config))
+        }
+        SelectorArg::Memorize => {
+            let config: MemorizeConfig = Default::default();
+            Box::new(MemorizeSelector::new(config))
+        }
+    })
+}
+
+fn main() -> Result<(), memorizer::traits::MemorizerError> {
+    let args = Args::parse();
+
+    let mut config: SimulationConfig = if let Some(path) = args.config.as_ref() {
+        let file =
+            std::fs::File::open(path).map_err(|e| format!("failed to open {path}: {e:?}"))?;
+        serde_yaml::from_reader(file)?
+    } else {
+        Default::default()
+    };
+    if let Some(days) = args.days {
+        config.days = days;
+    }
+    if let Some(seed) = args.seed {
+        config.seed = seed;
+    }
+
+    let mut reports: std::collections::BTreeMap<String, SimulationReport> = Default::default();
+    for selector in args.selector.iter() {
+        // Every run gets fresh learnables, the selector consumes them.
+        let mut learnables = vec![];
+        for learnable_file in args.learnables.iter() {
+            learnables.extend(load_text_learnables(learnable_file)?);
+        }
+
+        let report = simulate(learnables, make_selector(selector)?, &config);
+        println!(
+            "{selector:?}: {} reviews, {:.1} per day, retention {:.3} final {:.3} average",
+            report.total_reviews,
+            report.reviews_per_day,
+            report.final_retention,
+            report.average_retention
+        );
+        reports.insert(format!("{selector:?}"), report);
+    }
+
+    let file = std::fs::File::create(&args.output)
+        .map_err(|e| format!("failed to write to {}: {e:?}", args.output))?;
+    serde_yaml::to_writer(file, &reports)?;
+    Ok(())
+}
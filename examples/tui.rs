@@ -3,13 +3,27 @@
 
 // A pretty clunky terminal interface to ask questions...
 
+use memorizer::algorithm::ebisu::{EbisuConfig, EbisuSelector};
+use memorizer::algorithm::fsrs::{FsrsConfig, FsrsSelector};
+use memorizer::algorithm::half_life_regression::{
+    HalfLifeRegressionConfig, HalfLifeRegressionSelector,
+};
+use memorizer::algorithm::leech::{LeechConfig, LeechSelector};
+use memorizer::algorithm::leitner::{LeitnerConfig, LeitnerSelector};
 use memorizer::algorithm::memorize::recall_curve::{RecallCurveConfig, RecallCurveSelector};
-use memorizer::algorithm::super_memo_2::SuperMemo2Selector;
+use memorizer::algorithm::memorize::scheduler::{MemorizeConfig, MemorizeSelector};
+use memorizer::algorithm::sibling_bury::{SiblingBuryConfig, SiblingBurySelector};
+use memorizer::algorithm::super_memo_2::{SuperMemo2Config, SuperMemo2Selector};
+use memorizer::algorithm::transfer::TransferSelector;
 
+use memorizer::clock::{DayBoundary, TimeOfDay};
 use memorizer::recorder::YamlRecorder;
+use memorizer::rng::SelectorRng;
 use memorizer::text::{load_text_learnables, TextRepresentation};
 use memorizer::training::Training;
-use memorizer::traits::{Question, Record, RepresentationId, Score, Selector};
+use memorizer::traits::{
+    Explanation, Progress, Question, Record, RepresentationId, Score, Selector,
+};
 
 use clap::{Parser, ValueEnum};
 
@@ -73,12 +87,38 @@ struct App {
 
     /// The score to override with if set.
     default_score: Option<f64>,
+
+    /// Leech detection, the leeches are listed on exit.
+    leech: Option<LeechConfig>,
+
+    /// Predicted probability of recall for the current question, if the selector provides it.
+    predicted_recall: Option<f64>,
+
+    /// Number of questions due on each of the coming days, if the selector can forecast.
+    forecast: Option<Vec<usize>>,
+
+    /// Number of questions in each stage of learning, if the selector tracks this.
+    progress: Option<Progress>,
+
+    /// Whether to show why the selector chose the current question.
+    explain: bool,
+
+    /// Why the selector chose the current question, if it can explain this.
+    explanation: Option<Explanation>,
+
+    /// The last error from storing to the recorder, shown until the next successful store.
+    error: Option<String>,
 }
 
 #[derive(Debug, ValueEnum, Clone, Eq, PartialEq, Hash)]
 enum SelectorArg {
     SuperMemo2,
     RecallCurveSelector,
+    Fsrs,
+    Leitner,
+    HalfLifeRegression,
+    Ebisu,
+    Memorize,
 }
 
 /// Interactive text interface to learn decks.
@@ -89,6 +129,83 @@ struct Args {
     #[clap(value_enum, long)]
     selector: Option<SelectorArg>,
 
+    /// Configuration file for the selector, as written by the fit_selector example. Used by
+    /// the recall curve selector.
+    #[clap(long)]
+    selector_config: Option<String>,
+
+    /// Maximum number of new questions per day, used by the SuperMemo2, FSRS and Leitner
+    /// selectors.
+    #[clap(long)]
+    new_per_day: Option<usize>,
+
+    /// Maximum number of reviews per day, used by the SuperMemo2 selector.
+    #[clap(long, alias = "reviews-per-session")]
+    reviews_per_day: Option<usize>,
+
+    /// Learning step in minutes for new questions, used by the SuperMemo2 selector. Can be given
+    /// multiple times, for example `--learning-steps 1 --learning-steps 10`.
+    #[clap(long)]
+    learning_steps: Vec<f64>,
+
+    /// Relearning step in minutes after failing a review, used by the SuperMemo2 selector.
+    #[clap(long)]
+    relearning_steps: Vec<f64>,
+
+    /// Fraction by which intervals are fuzzed, used by the SuperMemo2 selector.
+    #[clap(long)]
+    fuzz: Option<f64>,
+
+    /// Pick the least busy day within the fuzz range, used by the SuperMemo2 selector.
+    #[clap(long)]
+    load_balance: bool,
+
+    /// Give partial credit to the questions related to the one answered, those of the same
+    /// learnable or with a shared representation.
+    #[clap(long)]
+    transfer: bool,
+
+    /// Hold back the other questions of a learnable for this many hours after one of them was
+    /// answered.
+    #[clap(long)]
+    bury_siblings_hours: Option<f64>,
+
+    /// Hold back the other questions of a learnable until the next day after one of them was
+    /// answered.
+    #[clap(long)]
+    bury_siblings_until_next_day: bool,
+
+    /// Suspend questions once they were failed on this many days, these leeches are listed on
+    /// exit such that they can be rewritten.
+    #[clap(long)]
+    leech_failures: Option<usize>,
+
+    /// Unsuspend all suspended and buried questions on startup.
+    #[clap(long)]
+    unsuspend_all: bool,
+
+    /// Unsuspend the questions of the learnable that has a representation with this text on
+    /// startup, for example a leech listed on exit. Can be given multiple times.
+    #[clap(long)]
+    unsuspend: Vec<String>,
+
+    /// Start in cram mode, all questions are practiced and answers are not recorded.
+    #[clap(long)]
+    cram: bool,
+
+    /// IANA timezone in which days start at --day-start, for example Europe/Amsterdam. Without
+    /// this or --day-start, intervals in days are 24 hours after the last answer.
+    #[clap(long)]
+    timezone: Option<chrono_tz::Tz>,
+
+    /// Local time at which a new day starts, as HH:MM, 04:00 if only --timezone is provided.
+    #[clap(long)]
+    day_start: Option<TimeOfDay>,
+
+    /// Seed for the random number generator of the selector, reproduces the order of questions.
+    #[clap(long)]
+    seed: Option<u64>,
+
     /// Set a score instead of calculating it from the presentation.
     #[clap(long)]
     default_score: Option<f64>,
@@ -108,23 +225,131 @@ impl App {
 
         let mut collected_learnables = vec![];
         for learnable_file in args.learnables.iter() {
-            let learnables = load_text_learnables(&learnable_file)?;
+            let learnables = load_text_learnables(learnable_file)?;
             collected_learnables.extend(learnables);
         }
 
         let selector_chosen = args.selector.unwrap_or(SelectorArg::SuperMemo2);
-        let selector: Box<dyn Selector>;
-        match selector_chosen {
+        let mut selector: Box<dyn Selector> = match selector_chosen {
             SelectorArg::SuperMemo2 => {
-                selector = Box::new(SuperMemo2Selector::new());
+                let config = SuperMemo2Config {
+                    new_per_day: args.new_per_day,
+                    reviews_per_day: args.reviews_per_day,
+                    learning_steps_minutes: args.learning_steps.clone(),
+                    relearning_steps_minutes: args.relearning_steps.clone(),
+                    fuzz: args.fuzz.unwrap_or(0.0),
+                    load_balance: args.load_balance,
+                };
+                Box::new(SuperMemo2Selector::new(config))
             }
             SelectorArg::RecallCurveSelector => {
-                let config: RecallCurveConfig = Default::default();
-                selector = Box::new(RecallCurveSelector::new(config));
+                let config: RecallCurveConfig = if let Some(path) = args.selector_config.as_ref() {
+                    let file = std::fs::File::open(path)
+                        .map_err(|e| format!("failed to open {path}: {e:?}"))?;
+                    serde_yaml::from_reader(file)?
+                } else {
+                    Default::default()
+                };
+                Box::new(RecallCurveSelector::new(config))
+            }
+            SelectorArg::Fsrs => {
+                let config = FsrsConfig {
+                    new_per_day: args.new_per_day,
+                    ..Default::default()
+                };
+                Box::new(FsrsSelector::new(config))
+            }
+            SelectorArg::Leitner => {
+                let config = LeitnerConfig {
+                    new_per_day: args.new_per_day,
+                    ..Default::default()
+                };
+                Box::new(LeitnerSelector::new(config)?)
             }
+            SelectorArg::HalfLifeRegression => {
+                let config: HalfLifeRegressionConfig = Default::default();
+                Box::new(HalfLifeRegressionSelector::new(config))
+            }
+            SelectorArg::Ebisu => {
+                let config: EbisuConfig = Default::default();
+                Box::new(EbisuSelector::new(config))
+            }
+            SelectorArg::Memorize => {
+                let config: MemorizeConfig = Default::default();
+                Box::new(MemorizeSelector::new(config))
+            }
+        };
+
+        if args.transfer {
+            selector = Box::new(TransferSelector::new(selector, Default::default()));
+        }
+
+        if args.bury_siblings_hours.is_some() || args.bury_siblings_until_next_day {
+            let mut config = SiblingBuryConfig {
+                until_next_day: args.bury_siblings_until_next_day,
+                ..Default::default()
+            };
+            if let Some(gap_hours) = args.bury_siblings_hours {
+                config.gap_hours = gap_hours;
+            }
+            selector = Box::new(SiblingBurySelector::new(selector, config));
+        }
+
+        let leech = args.leech_failures.map(|failures| LeechConfig {
+            failures,
+            suspend: true,
+        });
+        if let Some(config) = leech.as_ref() {
+            selector = Box::new(LeechSelector::new(selector, config.clone()));
+        }
+
+        if let Some(seed) = args.seed {
+            selector.set_rng(SelectorRng::from_seed(seed));
         }
 
-        let training = Training::new(collected_learnables, Box::new(recorder), selector);
+        let day_boundary = if args.timezone.is_some() || args.day_start.is_some() {
+            DayBoundary::local(
+                args.timezone.unwrap_or(chrono_tz::UTC),
+                args.day_start.unwrap_or(TimeOfDay { hour: 4, minute: 0 }),
+            )
+        } else {
+            DayBoundary::Rolling
+        };
+        let mut training = Training::with_day_boundary(
+            collected_learnables,
+            Box::new(recorder),
+            selector,
+            day_boundary,
+        )?;
+        training.set_cram(args.cram);
+        if args.unsuspend_all {
+            for question in training.suspended() {
+                training.unsuspend(&question)?;
+            }
+        }
+        for text in args.unsuspend.iter() {
+            let matches = |question: &Question| {
+                training
+                    .questions_from_learnable(question.learnable)
+                    .iter()
+                    .any(|q| {
+                        [q.from, q.to]
+                            .iter()
+                            .any(|id| training.representation(*id).text() == text)
+                    })
+            };
+            let questions = training
+                .suspended()
+                .into_iter()
+                .filter(matches)
+                .collect::<Vec<_>>();
+            if questions.is_empty() {
+                return Err(format!("no suspended question with {text:?}").into());
+            }
+            for question in questions.iter() {
+                training.unsuspend(question)?;
+            }
+        }
         Ok(App {
             input: String::new(),
             training,
@@ -137,6 +362,13 @@ impl App {
             question: Default::default(),
             record: Default::default(),
             default_score: args.default_score,
+            leech,
+            predicted_recall: None,
+            forecast: None,
+            progress: None,
+            explain: false,
+            explanation: None,
+            error: None,
         })
     }
 
@@ -169,18 +401,26 @@ impl App {
         self.state = ApplicationState::AnswerGiven;
     }
 
-    fn submit_record(&mut self) {
+    /// Store the answer, returns false and keeps the answer if storing it failed.
+    fn submit_record(&mut self) -> bool {
         if let Some(record) = self.record {
-            self.training
-                .finalize_answer(record)
-                .expect("Shouldn't fail");
+            if let Err(e) = self.training.finalize_answer(record) {
+                self.error = Some(format!("Failed to store the answer: {e}"));
+                return false;
+            }
         }
+        self.error = None;
+        true
     }
 
     fn populate_new(&mut self) {
         self.clear_fields();
+        self.forecast = self.training.forecast(8);
+        self.progress = self.training.progress();
         if let Some(q) = self.training.question() {
             self.question = q;
+            self.predicted_recall = self.training.predict_recall(&q);
+            self.explanation = self.training.explain();
             self.original = self
                 .training
                 .representation(self.question.from)
@@ -194,6 +434,8 @@ impl App {
             self.input.clear();
             self.state = ApplicationState::QuestionAsked;
         } else {
+            self.predicted_recall = None;
+            self.explanation = None;
             self.original.clear();
             self.transform = String::from("No more questions at the moment.");
             self.input.clear();
@@ -201,6 +443,30 @@ impl App {
         }
     }
 
+    fn toggle_cram(&mut self) {
+        let cram = !self.training.is_cram();
+        self.training.set_cram(cram);
+        self.record = None;
+        self.populate_new();
+    }
+
+    /// Suspend or bury the current question, dropping the answer if one was given.
+    fn hold_back(&mut self, bury: bool) {
+        let question = self.question;
+        let res = if bury {
+            self.training.bury(&question)
+        } else {
+            self.training.suspend(&question)
+        };
+        if let Err(e) = res {
+            self.error = Some(format!("Failed to store the suspension: {e}"));
+            return;
+        }
+        self.error = None;
+        self.record = None;
+        self.populate_new();
+    }
+
     fn modify_pending_score(&mut self, v: f64) {
         let record = self
             .record
@@ -224,7 +490,7 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
     let mut terminal = Terminal::new(backend)?;
 
     // Now run the application.
-    let res = run_app(&mut terminal, app);
+    let res = run_app(&mut terminal, &mut app);
 
     // restore terminal
     disable_raw_mode()?;
@@ -235,40 +501,61 @@ fn main() -> Result<(), memorizer::traits::MemorizerError> {
         println!("{:?}", err)
     }
 
+    // Speeds up the next start, the selector doesn't need to replay all records.
+    app.training.store_snapshot()?;
+
+    if let Some(config) = app.leech.as_ref() {
+        for question in app.training.leeches(config)? {
+            println!(
+                "Leech: {} ({}) -> {}",
+                app.training.representation(question.from).text(),
+                app.training.transform(question.transform).description(),
+                app.training.representation(question.to).text()
+            );
+        }
+    }
+
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        terminal.draw(|f| ui(f, app))?;
 
         if let Event::Key(key) = event::read()? {
             if key.code == KeyCode::Esc {
                 return Ok(());
             }
+            if key.code == KeyCode::F(2) {
+                app.toggle_cram();
+                continue;
+            }
+            if key.code == KeyCode::F(5) {
+                app.explain = !app.explain;
+                continue;
+            }
+            if key.code == KeyCode::F(3) || key.code == KeyCode::F(4) {
+                if app.state != ApplicationState::NoMoreQuestions {
+                    app.hold_back(key.code == KeyCode::F(4));
+                }
+                continue;
+            }
 
             match app.state {
                 ApplicationState::QuestionAsked => match key.code {
-                    KeyCode::Enter => {
-                        if !app.input.is_empty() {
-                            app.process_answer();
-                        }
+                    KeyCode::Enter if !app.input.is_empty() => {
+                        app.process_answer();
                     }
-                    KeyCode::Char(c) => {
-                        if app.state == ApplicationState::QuestionAsked {
-                            app.input.push(c);
-                        }
+                    KeyCode::Char(c) if app.state == ApplicationState::QuestionAsked => {
+                        app.input.push(c);
                     }
-                    KeyCode::Backspace => {
-                        if app.state == ApplicationState::QuestionAsked {
-                            app.input.pop();
-                        }
+                    KeyCode::Backspace if app.state == ApplicationState::QuestionAsked => {
+                        app.input.pop();
                     }
                     _ => {}
                 },
                 ApplicationState::AnswerGiven => match key.code {
-                    KeyCode::Enter => {
-                        app.submit_record();
+                    KeyCode::Enter if app.submit_record() => {
                         app.populate_new();
                     }
                     KeyCode::Right | KeyCode::Up => {
@@ -295,7 +582,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Length(3), // help text.
+                Constraint::Length(7), // help text.
                 Constraint::Length(1), // from
                 Constraint::Length(3),
                 Constraint::Length(1), // transform
@@ -322,11 +609,79 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" to exit, "),
         Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" to submit answer."),
+        Span::raw(" to submit answer, "),
+        Span::styled("F2", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" to toggle cram mode."),
     ];
+    let mut msg = msg;
+    if app.training.is_cram() {
+        msg.push(Span::styled(
+            " Cramming, answers are not recorded.",
+            Style::default().add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    let suspend_msg = vec![
+        Span::raw("Press "),
+        Span::styled("F3", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" to suspend the question, "),
+        Span::styled("F4", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" to bury it until tomorrow, "),
+        Span::styled("F5", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" to explain why it was chosen."),
+    ];
+
+    let mut lines = vec![Spans::from(msg), Spans::from(suspend_msg)];
+    if let Some(error) = app.error.as_ref() {
+        lines.push(Spans::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    if let Some(recall) = app.predicted_recall {
+        lines.push(Spans::from(Span::styled(
+            format!("Predicted recall: {:.0}%", recall * 100.0),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+    }
+    if let Some(forecast) = app.forecast.as_ref() {
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "Due now: {}, tomorrow: {}, in a week: {}",
+                forecast[0],
+                forecast[1],
+                forecast[1..].iter().sum::<usize>()
+            ),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+    }
+    if let Some(progress) = app.progress.as_ref() {
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{} due, {} in re-review, {} not yet started, {} mature",
+                progress.due, progress.re_review, progress.new, progress.mature
+            ),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+    }
+
+    if let Some(explanation) = app.explanation.as_ref().filter(|_| app.explain) {
+        let mut parts = vec![format!("Pool: {}", explanation.pool)];
+        if let Some(overdue) = explanation.overdue_seconds {
+            parts.push(format!("overdue {:.1} days", overdue / 86400.0));
+        }
+        if let Some(recall) = explanation.recall {
+            parts.push(format!("recall {:.0}%", recall * 100.0));
+        }
+        parts.extend(explanation.state.iter().map(|(k, v)| format!("{k}: {v}")));
+        lines.push(Spans::from(Span::styled(
+            parts.join(", "),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+    }
 
     let style = Style::default();
-    let mut text = Text::from(Spans::from(msg));
+    let mut text = Text::from(lines);
     text.patch_style(style);
     let help_message = Paragraph::new(text);
     f.render_widget(help_message, chunks[0]);
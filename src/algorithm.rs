@@ -1,11 +1,192 @@
 use crate::traits::*;
 
+/// Questions with an interval of at least this many days count as mature in the progress.
+const MATURE_INTERVAL_DAYS: f64 = 21.0;
+
+/// All records of the recorder, or if it can't provide those the records of the provided
+/// questions in order of time.
+pub(crate) fn history(recorder: &dyn Recorder, questions: &[Question]) -> Vec<Record> {
+    if let Ok(records) = recorder.get_records() {
+        return records;
+    }
+    let mut records = questions
+        .iter()
+        .flat_map(|q| {
+            recorder
+                .get_records_by_question(q)
+                .expect("Should return empty if unknown")
+        })
+        .collect::<Vec<_>>();
+    records.sort_by_key(|r| r.time);
+    records
+}
+
+/// Clock for tests, at an arbitrary moment that is the start of a day in UTC.
+#[cfg(test)]
+pub(crate) fn test_clock() -> std::sync::Arc<crate::clock::ManualClock> {
+    std::sync::Arc::new(crate::clock::ManualClock::new(
+        std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1000 * 24 * 60 * 60),
+    ))
+}
+
+/// Questions for tests, one for each learnable.
+#[cfg(test)]
+pub(crate) fn test_questions(count: Id) -> Vec<Question> {
+    (0..count)
+        .map(|i| Question {
+            learnable: LearnableId(i),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Bin the moments questions become due into days for a forecast, see [`Selector::forecast`].
+fn due_per_day(
+    now: std::time::SystemTime,
+    due_times: impl Iterator<Item = std::time::SystemTime>,
+    days: usize,
+) -> Vec<usize> {
+    const DAY: f64 = 24.0 * 60.0 * 60.0;
+    let mut res = vec![0; days];
+    for due in due_times {
+        let until_due = due.duration_since(now).unwrap_or_default().as_secs_f64();
+        if let Some(v) = res.get_mut((until_due / DAY).ceil() as usize) {
+            *v += 1;
+        }
+    }
+    res
+}
+
+/// Like [`due_per_day`], but at most limit of the questions are asked per day, minus the ones
+/// asked today already for the first day. The others are asked on the following days, as the
+/// selector would.
+fn limited_due_per_day(
+    now: std::time::SystemTime,
+    due_times: impl Iterator<Item = std::time::SystemTime>,
+    limit: Option<usize>,
+    asked_today: usize,
+    days: usize,
+) -> Vec<usize> {
+    let mut res = due_per_day(now, due_times, days);
+    let Some(limit) = limit else {
+        return res;
+    };
+    let mut backlog = 0;
+    for (day, v) in res.iter_mut().enumerate() {
+        backlog += *v;
+        let remaining = if day == 0 {
+            limit.saturating_sub(asked_today)
+        } else {
+            limit
+        };
+        *v = backlog.min(remaining);
+        backlog -= *v;
+    }
+    res
+}
+
+/// Add the forecasts together, per day.
+fn add_forecasts(mut a: Vec<usize>, b: Vec<usize>) -> Vec<usize> {
+    a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+    a
+}
+
+/// Seconds from the first time until the second, negative if the second is earlier.
+fn seconds_between(from: std::time::SystemTime, to: std::time::SystemTime) -> f64 {
+    match to.duration_since(from) {
+        Ok(d) => d.as_secs_f64(),
+        Err(e) => -e.duration().as_secs_f64(),
+    }
+}
+
+/// Fraction of the interval a question is postponed by when it gets full partial credit.
+const PARTIAL_CREDIT_POSTPONE: f64 = 0.5;
+
+/// The moment a question with this interval in days is postponed to for partial credit given at
+/// the provided time, see [`Selector::store_partial_credit`]. Postpones at least a day.
+fn partial_credit_postpone(
+    boundary: &crate::clock::DayBoundary,
+    interval_days: f64,
+    credit: Score,
+    time: std::time::SystemTime,
+) -> std::time::SystemTime {
+    let days = (interval_days * credit * PARTIAL_CREDIT_POSTPONE)
+        .round()
+        .max(1.0);
+    boundary.days_after(time, days as u64)
+}
+
+/// State in a snapshot, along with the selector and configuration it was taken with.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct TaggedState {
+    selector: String,
+    config: serde_yaml::Value,
+    state: serde_yaml::Value,
+}
+
+/// Snapshot of the state of the named selector with this configuration, see [`restore_tagged`].
+fn snapshot_tagged(
+    selector: &str,
+    config: &impl serde::Serialize,
+    state: &impl serde::Serialize,
+) -> Option<serde_yaml::Value> {
+    serde_yaml::to_value(TaggedState {
+        selector: selector.to_owned(),
+        config: serde_yaml::to_value(config).ok()?,
+        state: serde_yaml::to_value(state).ok()?,
+    })
+    .ok()
+}
+
+/// The state from [`snapshot_tagged`]. Fails if the snapshot was taken by another selector or
+/// with another configuration, the state may not hold for this one.
+fn restore_tagged<T: serde::de::DeserializeOwned>(
+    selector: &str,
+    config: &impl serde::Serialize,
+    state: &serde_yaml::Value,
+) -> Result<T, crate::traits::MemorizerError> {
+    let tagged: TaggedState = serde_yaml::from_value(state.clone())?;
+    if tagged.selector != selector {
+        return Err(format!("snapshot is for {}, not {selector}", tagged.selector).into());
+    }
+    if tagged.config != serde_yaml::to_value(config)? {
+        return Err("snapshot is for a different configuration".into());
+    }
+    Ok(serde_yaml::from_value(tagged.state)?)
+}
+
+/// Check that the state in a snapshot is for exactly these questions, in the same order.
+fn check_snapshot_questions(
+    questions: &[crate::traits::Question],
+    snapshot: impl Iterator<Item = crate::traits::Question>,
+) -> Result<(), crate::traits::MemorizerError> {
+    if !questions.iter().copied().eq(snapshot) {
+        return Err("snapshot is for different questions".into());
+    }
+    Ok(())
+}
+
 // As retrieved from https://en.wikipedia.org/wiki/SuperMemo
 // https://en.wikipedia.org/w/index.php?title=SuperMemo&oldid=1087602144
 pub mod super_memo_2 {
     use crate::traits::*;
+    use serde::{Deserialize, Serialize};
 
-    #[derive(Debug, Clone)]
+    /// One day, the unit of the intervals after the learning steps.
+    const DAY: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    /// Stage of learning for a question.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    enum Stage {
+        /// In the learning steps of a new question, at this step.
+        Learning(usize),
+        /// In the relearning steps after failing a review, at this step.
+        Relearning(usize),
+        /// Graduated from the steps, the intervals are in days.
+        Review,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     struct QuestionState {
         /// The repetition number n, which is the number of times the card has been
         /// successfully recalled (meaning it was given a grade ≥ 3) in a row since the last
@@ -17,7 +198,10 @@ pub mod super_memo_2 {
         easiness_factor: f64, // EF, initially 2.5
         /// The inter-repetition interval I, which is the length of time (in days) SuperMemo
         /// will wait after the previous review before asking the user to review the card again.
-        inter_repetition: u64, // I, Inter repetition interval, days
+        /// This is shorter than a day during the learning steps.
+        inter_repetition: std::time::Duration, // I, Inter repetition interval
+        /// Whether the question is in the learning or relearning steps.
+        stage: Stage,
     }
     /*
         Every time the user starts a review session, SuperMemo provides the user with the cards
@@ -42,13 +226,21 @@ pub mod super_memo_2 {
             QuestionState {
                 repetition_number: 0,
                 easiness_factor: 2.5,
-                inter_repetition: 0,
+                inter_repetition: std::time::Duration::ZERO,
+                stage: Stage::Learning(0),
             }
         }
     }
 
+    /// Convert a score into the 0 to 5 grade used by SuperMemo, for the selectors that share its
+    /// definition of a successful recall.
+    pub(crate) fn score_to_grade(score: Score) -> u64 {
+        QuestionState::score_to_grade(score)
+    }
+
     impl QuestionState {
-        pub fn score_to_grade(score: f64) -> u64 {
+        /// Convert a score into the 0 to 5 grade used by SuperMemo.
+        pub fn score_to_grade(score: Score) -> u64 {
             if score <= 0.0 {
                 0
             } else if score <= 0.2 {
@@ -64,38 +256,107 @@ pub mod super_memo_2 {
             }
         }
 
-        pub fn update(&mut self, user_grade: u64) {
+        /// Update the state with a grade, elapsed_days is the number of days since the previous
+        /// answer, this affects the interval growth if the review was early or overdue.
+        pub fn update(&mut self, user_grade: u64, elapsed_days: f64, config: &SuperMemo2Config) {
             assert!(user_grade <= 5);
+            let minutes = |m: f64| std::time::Duration::from_secs_f64(m * 60.0);
+
+            // The learning steps, a correct response moves to the next step and an incorrect one
+            // back to the first. The easiness factor is unaffected by the steps.
+            if let Stage::Learning(step) | Stage::Relearning(step) = self.stage {
+                let relearning = self.stage != Stage::Learning(step);
+                let steps = if relearning {
+                    &config.relearning_steps_minutes
+                } else {
+                    &config.learning_steps_minutes
+                };
+                let next = if user_grade >= 3 { step + 1 } else { 0 };
+                if let Some(interval) = steps.get(next) {
+                    self.inter_repetition = minutes(*interval);
+                    self.stage = if relearning {
+                        Stage::Relearning(next)
+                    } else {
+                        Stage::Learning(next)
+                    };
+                    return;
+                }
+                self.stage = Stage::Review;
+                if relearning {
+                    // Graduate with the interval for an incorrect response.
+                    self.inter_repetition = DAY;
+                    return;
+                }
+                // Graduating from the learning steps is the first correct response.
+            }
+
             if user_grade >= 3 {
                 // correct response
-                if self.repetition_number == 1 {
-                    self.inter_repetition = 1;
+                if self.repetition_number == 0 {
+                    self.inter_repetition = DAY;
                 } else if self.repetition_number == 1 {
-                    self.inter_repetition = 6;
+                    self.inter_repetition = DAY * 6;
                 } else {
-                    self.inter_repetition =
-                        ((self.inter_repetition as f64) * self.easiness_factor).round() as u64;
+                    let scheduled = self.inter_repetition.as_secs_f64() / DAY.as_secs_f64();
+                    // Grow from the time that actually passed. An overdue question that was still
+                    // recalled gets a bonus for the delay, half of it if recalling took effort.
+                    // An early review grows less, but the interval never shrinks.
+                    let elapsed = if elapsed_days >= scheduled {
+                        let overdue = elapsed_days - scheduled;
+                        if user_grade >= 4 {
+                            scheduled + overdue
+                        } else {
+                            scheduled + overdue / 2.0
+                        }
+                    } else {
+                        elapsed_days
+                    };
+                    let days = (elapsed * self.easiness_factor).round().max(scheduled);
+                    self.inter_repetition = DAY * days as u32;
                 }
                 self.repetition_number += 1;
             } else {
                 // incorrect response
                 self.repetition_number = 0;
-                self.inter_repetition = 1;
+                self.inter_repetition = DAY;
+                if let Some(interval) = config.relearning_steps_minutes.first() {
+                    self.inter_repetition = minutes(*interval);
+                    self.stage = Stage::Relearning(0);
+                }
             }
             // update EF based on correctness.
             let s = (5 - user_grade) as f64;
-            self.easiness_factor = self.easiness_factor + (0.1 - s * (0.08 + s * 0.02));
+            self.easiness_factor += 0.1 - s * (0.08 + s * 0.02);
             if self.easiness_factor < 1.3 {
                 self.easiness_factor = 1.3;
             }
         }
 
-        pub fn inter_repetition(&self) -> u64 {
+        /// Count a new question as recalled once already, from partial credit. A correct first
+        /// answer then skips the learning steps and the first interval.
+        fn prime(&mut self) {
+            self.repetition_number = 1;
+            self.inter_repetition = DAY;
+            self.stage = Stage::Review;
+        }
+
+        pub fn inter_repetition(&self) -> std::time::Duration {
             self.inter_repetition
         }
+
+        /// Whether the question was never answered.
+        pub fn is_new(&self) -> bool {
+            // The interval is only zero before the first answer.
+            self.inter_repetition.is_zero()
+        }
+
+        /// Whether the question was answered and is in the learning or relearning steps.
+        pub fn is_learning(&self) -> bool {
+            !self.is_new() && self.stage != Stage::Review
+        }
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     struct QuestionInfo {
         /// The question itself.
         question: Question,
@@ -108,82 +369,392 @@ pub mod super_memo_2 {
 
         /// The internal state for this question.
         state: QuestionState,
+
+        /// Not due before this moment, from partial credit for answers to related questions.
+        /// Cleared by an answer.
+        postponed: Option<std::time::SystemTime>,
+
+        /// Whether this new question got partial credit, see [`QuestionState::prime`]. Cleared
+        /// by an answer.
+        #[serde(default)]
+        primed: bool,
+    }
+
+    impl QuestionInfo {
+        /// The moment this question is due for review.
+        fn due_time(&self, boundary: &crate::clock::DayBoundary) -> std::time::SystemTime {
+            // The learning steps are exact, they are shorter than a day.
+            if self.state.is_learning() {
+                return self.last_time + self.state.inter_repetition();
+            }
+            // See DayBoundary::due_after, this snaps to the start of a day if configured.
+            let due = boundary.due_after(self.last_time, self.state.inter_repetition());
+            self.postponed.map(|p| p.max(due)).unwrap_or(due)
+        }
+
+        /// The interval in whole days.
+        fn interval_days(&self) -> f64 {
+            (self.state.inter_repetition().as_secs_f64() / DAY.as_secs_f64()).round()
+        }
+    }
+
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+    pub struct SuperMemo2Config {
+        /// Maximum number of questions asked for the first time per day, None for no limit.
+        pub new_per_day: Option<usize>,
+        /// Maximum number of answers to previously answered questions per day, None for no limit.
+        /// Answers during the learning steps don't count as reviews.
+        #[serde(alias = "reviews_per_session")]
+        pub reviews_per_day: Option<usize>,
+        /// Intervals in minutes for new questions before they graduate to intervals in days, for
+        /// example `[1.0, 10.0]`. A correct answer moves to the next step, an incorrect one back to
+        /// the first step. No steps if empty.
+        #[serde(default)]
+        pub learning_steps_minutes: Vec<f64>,
+        /// Intervals in minutes after an incorrect answer to a graduated question, before it
+        /// continues with an interval of a day. No steps if empty.
+        #[serde(default)]
+        pub relearning_steps_minutes: Vec<f64>,
+        /// Fraction by which intervals of at least three days are made longer or shorter, such
+        /// that questions answered together don't all come due on the same day. For example 0.05
+        /// for up to 5%, at least a day. The fuzz is derived from the question, not random, such
+        /// that replaying the history gives the same schedule.
+        #[serde(default)]
+        pub fuzz: f64,
+        /// Instead of the fuzzed interval, pick the day with the fewest due questions within the
+        /// range of the fuzz.
+        #[serde(default)]
+        pub load_balance: bool,
+    }
+
+    /// Intervals shorter than this many days are not fuzzed.
+    const FUZZ_MIN_DAYS: f64 = 3.0;
+
+    /// Deterministic number between -1 and 1 derived from the question and its repetition
+    /// number, this is the fuzz for the interval. Uses the splitmix64 finalizer.
+    fn fuzz_factor(question: &Question, repetition: u64) -> f64 {
+        let mut x = question.learnable.0
+            ^ question.from.0.rotate_left(16)
+            ^ question.transform.0.rotate_left(32)
+            ^ question.to.0.rotate_left(48)
+            ^ repetition.wrapping_mul(0x9E3779B97F4A7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+        x ^= x >> 31;
+        (x as f64 / u64::MAX as f64) * 2.0 - 1.0
+    }
+
+    /// State of the selector in a snapshot.
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Snapshot {
+        questions: Vec<QuestionInfo>,
+        /// The day the counts for the limits are for.
+        today: i64,
+        new_today: usize,
+        reviews_today: usize,
     }
 
     /// A selector that implements the SuperMemo2 algorithm.
     #[derive(Debug)]
     pub struct SuperMemo2Selector {
         questions: Vec<QuestionInfo>,
+        clock: std::sync::Arc<dyn Clock>,
+        rng: crate::rng::SelectorRng,
+        config: SuperMemo2Config,
+        /// The day the counts for the limits are for.
+        today: i64,
+        /// Number of questions answered for the first time today.
+        new_today: usize,
+        /// Number of answers to previously answered questions today.
+        reviews_today: usize,
+        day_boundary: crate::clock::DayBoundary,
+        /// Number of graduated questions due on each day, for the load balancing.
+        due_load: std::collections::HashMap<i64, usize>,
+        /// Why the last question was chosen.
+        last_explanation: Option<Explanation>,
     }
     impl SuperMemo2Selector {
-        pub fn new() -> Self {
-            SuperMemo2Selector { questions: vec![] }
+        pub fn new(config: SuperMemo2Config) -> Self {
+            SuperMemo2Selector {
+                questions: vec![],
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                rng: Default::default(),
+                config,
+                today: 0,
+                new_today: 0,
+                reviews_today: 0,
+                day_boundary: Default::default(),
+                due_load: Default::default(),
+                last_explanation: None,
+            }
+        }
+
+        /// Explanation for choosing the question from the pool.
+        fn explanation(&self, question: &Question, pool: &str) -> Option<Explanation> {
+            let z = self.questions.iter().find(|z| z.question == *question)?;
+            let interval = z.state.inter_repetition().as_secs_f64();
+            let interval = if z.state.is_learning() {
+                format!("{:.0} minutes", interval / 60.0)
+            } else {
+                format!("{:.0} days", interval / DAY.as_secs_f64())
+            };
+            let due_time = z.due_time(&self.day_boundary);
+            Some(Explanation {
+                question: *question,
+                pool: pool.to_owned(),
+                overdue_seconds: (!z.state.is_new())
+                    .then(|| super::seconds_between(due_time, self.clock.now())),
+                recall: None,
+                state: vec![
+                    (
+                        "easiness factor".to_owned(),
+                        format!("{:.2}", z.state.easiness_factor),
+                    ),
+                    (
+                        "repetition number".to_owned(),
+                        z.state.repetition_number.to_string(),
+                    ),
+                    ("interval".to_owned(), interval),
+                    ("stage".to_owned(), format!("{:?}", z.state.stage)),
+                ],
+            })
+        }
+
+        /// Update the question at index with an answer, this schedules the next review.
+        fn apply_record(&mut self, index: usize, record: &Record) {
+            if let Some(day) = self.due_day(index) {
+                if let Some(load) = self.due_load.get_mut(&day) {
+                    *load = load.saturating_sub(1);
+                }
+            }
+            let z = &mut self.questions[index];
+            let grade = QuestionState::score_to_grade(record.score);
+            // Whole days, matching the intervals snapped to the day boundary.
+            let elapsed_days = self.day_boundary.days_between(z.last_time, record.time);
+            if z.primed && grade >= 3 {
+                z.state.prime();
+            }
+            z.primed = false;
+            z.state.update(grade, elapsed_days as f64, &self.config);
+            // mark for re-review, the learning steps take care of this until graduated.
+            z.pending_re_review = grade < 4 && !z.state.is_learning();
+            z.last_time = record.time;
+            z.postponed = None;
+            self.fuzz_interval(index);
+            if let Some(day) = self.due_day(index) {
+                *self.due_load.entry(day).or_default() += 1;
+            }
+        }
+
+        /// The day a graduated question is due, these make up the load for the balancing.
+        fn due_day(&self, index: usize) -> Option<i64> {
+            let z = &self.questions[index];
+            if z.state.is_new() || z.state.is_learning() {
+                return None;
+            }
+            Some(self.day_boundary.day(z.due_time(&self.day_boundary)))
+        }
+
+        /// Apply the fuzz and load balancing to the interval of a question that was just answered.
+        fn fuzz_interval(&mut self, index: usize) {
+            let z = &self.questions[index];
+            if z.state.is_learning() || self.config.fuzz <= 0.0 {
+                return;
+            }
+            let days = (z.state.inter_repetition().as_secs_f64() / DAY.as_secs_f64()).round();
+            if days < FUZZ_MIN_DAYS {
+                return;
+            }
+            let spread = (days * self.config.fuzz).round().max(1.0) as i64;
+            let days = days as i64;
+            let fuzz = fuzz_factor(&z.question, z.state.repetition_number);
+            let fuzzed = days + (fuzz * spread as f64).round() as i64;
+            let chosen = if self.config.load_balance {
+                // The least busy day, closest to the fuzzed interval.
+                let boundary = &self.day_boundary;
+                (days - spread..=days + spread)
+                    .min_by_key(|d| {
+                        let due = boundary.days_after(z.last_time, (*d).max(1) as u64);
+                        let load = self.due_load.get(&boundary.day(due)).copied();
+                        (load.unwrap_or(0), (d - fuzzed).abs())
+                    })
+                    .unwrap_or(fuzzed)
+            } else {
+                fuzzed
+            };
+            self.questions[index].state.inter_repetition = DAY * chosen.max(1) as u32;
+        }
+
+        /// Count the graduated questions due on each day, for the load balancing.
+        fn update_due_load(&mut self) {
+            self.due_load.clear();
+            for index in 0..self.questions.len() {
+                if let Some(day) = self.due_day(index) {
+                    *self.due_load.entry(day).or_default() += 1;
+                }
+            }
+        }
+
+        /// Start counting towards the limits again if a new day started.
+        fn update_today(&mut self) {
+            let today = self.day_boundary.day(self.clock.now());
+            if today != self.today {
+                self.today = today;
+                self.new_today = 0;
+                self.reviews_today = 0;
+            }
+        }
+
+        /// Count an answer towards the limits of today.
+        fn count_answer(&mut self, index: usize) {
+            let state = &self.questions[index].state;
+            if state.is_new() {
+                self.new_today += 1;
+            } else if !state.is_learning() {
+                self.reviews_today += 1;
+            }
+        }
+
+        fn may_ask_new(&self) -> bool {
+            self.config
+                .new_per_day
+                .map(|limit| self.new_today < limit)
+                .unwrap_or(true)
+        }
+
+        fn may_ask_review(&self) -> bool {
+            self.config
+                .reviews_per_day
+                .map(|limit| self.reviews_today < limit)
+                .unwrap_or(true)
+        }
+    }
+
+    impl Default for SuperMemo2Selector {
+        fn default() -> Self {
+            Self::new(Default::default())
         }
     }
 
     impl Selector for SuperMemo2Selector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock;
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.rng = rng;
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.day_boundary = boundary;
+        }
+
         fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        /// Replay the answers and the partial credit in the order they were given, such that
+        /// the load balancing sees the same schedule as when they were given, and new questions
+        /// that got credit are primed for their first answer.
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
             self.questions.clear();
-            let now = std::time::SystemTime::now();
+            self.due_load.clear();
+            let now = self.clock.now();
+            self.today = self.day_boundary.day(now);
+            self.new_today = 0;
+            self.reviews_today = 0;
+            let mut indices = std::collections::HashMap::new();
             for question in questions.iter() {
-                let records = recorder
-                    .get_records_by_question(question)
-                    .expect("Should return empty if unknown");
-
-                // Create the state and iterate through all records to update the state.
-                let mut state = QuestionState::default();
-                let mut last_time = now;
-                for record in records.iter() {
-                    last_time = record.time;
-                    let grade = QuestionState::score_to_grade(record.score);
-                    state.update(grade);
-                }
-
+                indices.insert(*question, self.questions.len());
                 self.questions.push(QuestionInfo {
                     question: *question,
-                    last_time,
-                    state,
+                    last_time: now,
+                    state: QuestionState::default(),
                     pending_re_review: false,
+                    postponed: None,
+                    primed: false,
                 });
             }
+
+            let mut records = super::history(recorder, questions);
+            records.sort_by_key(|r| r.time);
+            let mut credit = credit.iter().peekable();
+            for record in records.iter() {
+                while let Some(c) = credit.next_if(|c| c.time <= record.time) {
+                    self.store_partial_credit(c);
+                }
+                let Some(&index) = indices.get(&record.question) else {
+                    continue;
+                };
+                // Count today's answers from the history, such that the limits hold across
+                // restarts.
+                if self.day_boundary.same_day(now, record.time) {
+                    self.count_answer(index);
+                }
+                self.apply_record(index, record);
+            }
+            for c in credit {
+                self.store_partial_credit(c);
+            }
+
+            // Re-reviews are for the session the answer was given in.
+            for z in self.questions.iter_mut() {
+                z.pending_re_review = false;
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
         }
 
         /// Retrieve a question to ask. The order in which the questions are retrieved are random
         /// but the set it draws from is the same unless a question is answered.
-        fn get_question(&mut self) -> Option<Question> {
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
             use rand::seq::SliceRandom;
             // Stage one:
             // Every time the user starts a review session, SuperMemo provides the user with the
             // cards whose last review occurred at least I days ago.
 
-            let now = std::time::SystemTime::now();
-
-            // Subtract a few hours, this allows for testing at an earlier timestamp than exactly 24 hours for
-            // a day, preventing the interval from 'moving forward' in time when reviewing at roughly the same
-            // time each day.
-            let interval_subtract = std::time::Duration::new(60 * 60 * 6, 0);
+            self.update_today();
+            self.last_explanation = None;
+            let now = self.clock.now();
+            let may_ask_new = self.may_ask_new();
+            let may_ask_review = self.may_ask_review();
+            let boundary = &self.day_boundary;
             let questions_pending_review = self
                 .questions
                 .iter()
+                .filter(|z| now >= z.due_time(boundary) && allowed(&z.question))
                 .filter(|z| {
-                    let duration_since_last =
-                        now.duration_since(z.last_time).expect("can this fail?");
-                    // println!("duration_since_last: {:?}", duration_since_last);
-                    let interval_to_days =
-                        std::time::Duration::new(24 * 60 * 60 * z.state.inter_repetition(), 0);
-                    let interval_to_days = interval_to_days.saturating_sub(interval_subtract);
-                    duration_since_last > interval_to_days
+                    if z.state.is_new() {
+                        may_ask_new
+                    } else {
+                        z.state.is_learning() || may_ask_review
+                    }
                 })
                 .collect::<Vec<_>>();
 
             // println!("questions_pending_review: {questions_pending_review:?}");
             if !questions_pending_review.is_empty() {
-                return Some(
-                    questions_pending_review
-                        .choose(&mut rand::thread_rng())
-                        .unwrap()
-                        .question,
-                );
+                let question = questions_pending_review
+                    .choose(&mut self.rng)
+                    .unwrap()
+                    .question;
+                let is_new = self
+                    .questions
+                    .iter()
+                    .any(|z| z.question == question && z.state.is_new());
+                let pool = if is_new { "new" } else { "due" };
+                self.last_explanation = self.explanation(&question, pool);
+                return Some(question);
             }
 
             // After all scheduled reviews are complete, SuperMemo asks the user to re-review any cards
@@ -192,15 +763,15 @@ pub mod super_memo_2 {
             let questions_pending_re_review = self
                 .questions
                 .iter()
-                .filter(|z| z.pending_re_review)
+                .filter(|z| z.pending_re_review && may_ask_review && allowed(&z.question))
                 .collect::<Vec<_>>();
             if !questions_pending_re_review.is_empty() {
-                return Some(
-                    questions_pending_re_review
-                        .choose(&mut rand::thread_rng())
-                        .unwrap()
-                        .question,
-                );
+                let question = questions_pending_re_review
+                    .choose(&mut self.rng)
+                    .unwrap()
+                    .question;
+                self.last_explanation = self.explanation(&question, "re-review");
+                return Some(question);
             }
 
             // Reached the end of the session, no more questions to ask.
@@ -209,283 +780,4627 @@ pub mod super_memo_2 {
 
         /// Store answer to a question.
         fn store_record(&mut self, record: &Record) {
-            let z = self
+            let index = self
                 .questions
-                .iter_mut()
-                .find(|v| v.question == record.question)
+                .iter()
+                .position(|v| v.question == record.question)
                 .expect("Passed question for which we don't have a record.");
-            let grade = QuestionState::score_to_grade(record.score);
-            z.pending_re_review = grade < 4; // mark for re-review
-            z.state.update(grade);
-            z.last_time = record.time;
+            self.update_today();
+            self.count_answer(index);
+            self.apply_record(index, record);
         }
-    }
-}
 
-pub mod dummy {
-    use super::*;
-    /// Trivial selector that yields entries in order.
-    #[derive(Debug)]
-    pub struct DummySelector {
-        edges: Vec<(Question, Vec<Score>)>,
-    }
-    impl DummySelector {
-        pub fn new() -> Self {
-            DummySelector { edges: vec![] }
+        /// Postpone a graduated question, this doesn't count towards the limits and doesn't
+        /// change the easiness or interval. A new question is primed instead if the credit counts
+        /// as a correct response, see [`QuestionState::prime`].
+        fn store_partial_credit(&mut self, record: &Record) {
+            let Some(index) = self
+                .questions
+                .iter()
+                .position(|v| v.question == record.question)
+            else {
+                return;
+            };
+            let z = &mut self.questions[index];
+            if z.state.is_new() {
+                z.primed |= QuestionState::score_to_grade(record.score) >= 3;
+                return;
+            }
+            if z.state.is_learning() || z.pending_re_review {
+                return;
+            }
+            let postponed = super::partial_credit_postpone(
+                &self.day_boundary,
+                z.interval_days(),
+                record.score,
+                record.time,
+            );
+            if postponed <= z.due_time(&self.day_boundary) {
+                return;
+            }
+            if let Some(day) = self.due_day(index) {
+                if let Some(load) = self.due_load.get_mut(&day) {
+                    *load = load.saturating_sub(1);
+                }
+            }
+            self.questions[index].postponed = Some(postponed);
+            if let Some(day) = self.due_day(index) {
+                *self.due_load.entry(day).or_default() += 1;
+            }
         }
-    }
 
-    impl Selector for DummySelector {
-        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
-            self.edges.clear();
-            for q in questions.iter() {
-                let records = recorder
-                    .get_records_by_question(q)
-                    .expect("Should return empty if unknown");
-                let scores = records.iter().map(|x| x.score).collect::<_>();
-                self.edges.push((*q, scores));
+        /// Forecast with the new questions and reviews capped by the daily limits, the learning
+        /// steps are not limited.
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            let now = self.clock.now();
+            let boundary = &self.day_boundary;
+            let due_time = |z: &QuestionInfo| {
+                if z.pending_re_review {
+                    now
+                } else {
+                    z.due_time(boundary)
+                }
+            };
+            // The counts are reset by the first answer of a new day, see update_today.
+            let (new_today, reviews_today) = if self.today == boundary.day(now) {
+                (self.new_today, self.reviews_today)
+            } else {
+                (0, 0)
+            };
+            let due_times = |f: fn(&QuestionState) -> bool| {
+                self.questions
+                    .iter()
+                    .filter(move |z| f(&z.state))
+                    .map(due_time)
+            };
+            let new = super::limited_due_per_day(
+                now,
+                due_times(QuestionState::is_new),
+                self.config.new_per_day,
+                new_today,
+                days,
+            );
+            let reviews = super::limited_due_per_day(
+                now,
+                due_times(|s| !s.is_new() && !s.is_learning()),
+                self.config.reviews_per_day,
+                reviews_today,
+                days,
+            );
+            let learning = super::due_per_day(now, due_times(QuestionState::is_learning), days);
+            Some(super::add_forecasts(
+                super::add_forecasts(new, reviews),
+                learning,
+            ))
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            let now = self.clock.now();
+            let mut progress = Progress {
+                total: self.questions.len(),
+                ..Default::default()
+            };
+            for z in self.questions.iter() {
+                if z.state.is_new() {
+                    progress.new += 1;
+                } else if now >= z.due_time(&self.day_boundary) {
+                    progress.due += 1;
+                } else if z.pending_re_review {
+                    progress.re_review += 1;
+                }
+                if z.state.inter_repetition().as_secs_f64() / DAY.as_secs_f64()
+                    >= super::MATURE_INTERVAL_DAYS
+                {
+                    progress.mature += 1;
+                }
             }
+            Some(progress)
         }
 
-        /// Retrieve a question to ask.
-        fn get_question(&mut self) -> Option<Question> {
-            // Just return things in order.
-            let first = self.edges.remove(0);
-            self.edges.push(first.clone());
-            Some(first.0)
+        fn explain(&self) -> Option<Explanation> {
+            self.last_explanation.clone()
         }
 
-        /// Store answer to a question.
-        fn store_record(&mut self, _record: &Record) {}
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            let snapshot = Snapshot {
+                questions: self.questions.clone(),
+                today: self.today,
+                new_today: self.new_today,
+                reviews_today: self.reviews_today,
+            };
+            let config = (&self.config, &self.day_boundary);
+            super::snapshot_tagged("SuperMemo2", &config, &snapshot)
+        }
+
+        /// Restore the state, this keeps the pending re-reviews of today. The limits and the
+        /// re-reviews start over if the snapshot is from an earlier day.
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            _recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let snapshot: Snapshot =
+                super::restore_tagged("SuperMemo2", &(&self.config, &self.day_boundary), state)?;
+            super::check_snapshot_questions(
+                questions,
+                snapshot.questions.iter().map(|z| z.question),
+            )?;
+            self.questions = snapshot.questions;
+            self.today = snapshot.today;
+            self.new_today = snapshot.new_today;
+            self.reviews_today = snapshot.reviews_today;
+            // Re-reviews are for the session the answer was given in, as in set_questions.
+            if self.today != self.day_boundary.day(self.clock.now()) {
+                for z in self.questions.iter_mut() {
+                    z.pending_re_review = false;
+                }
+            }
+            self.update_today();
+            self.update_due_load();
+            Ok(())
+        }
     }
-}
 
-// Enhancing human learning via spaced repetition optimization
-// https://www.pnas.org/doi/full/10.1073/pnas.1815156116
-// https://github.com/Networks-Learning/memorize/
-pub mod memorize {
-    /*
-        Summarized from the paper;
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::{test_clock, test_questions};
+        use crate::clock::ManualClock;
+        use crate::recorder::MemoryRecorder;
+        use std::sync::Arc;
 
-        Exponential forgetting curve model of memory
-        Recall m(t) of item after review is 1.0, m(t) decays with forgetting rate n(t)
+        /// Recorder that only implements the required methods.
+        #[derive(Debug)]
+        struct MinimalRecorder(MemoryRecorder);
 
-            m(t) = exp( -n(t) * (t - t_last_review) )
+        impl Recorder for MinimalRecorder {
+            fn store_record(&mut self, record: &Record) -> Result<(), MemorizerError> {
+                self.0.store_record(record)
+            }
 
-        Estimated forgetting rate changes based on correct or failed recall.
-            Correct recall:
-                n(t + dt) = (1 - alpha) * n(t)
-            Failed recall:
-                n(t + dt) = (1 + beta) * n(t)
+            fn get_records_by_question(
+                &self,
+                question: &Question,
+            ) -> Result<Vec<Record>, MemorizerError> {
+                self.0.get_records_by_question(question)
+            }
+        }
 
-        The reviewing intensity is then given by:
-            u(t) = q^(-0.5) * (1 - m(t))
+        #[test]
+        fn test_minimal_recorder() {
+            let clock = test_clock();
+            let question = Question::default();
+            let mut recorder = MinimalRecorder(MemoryRecorder::new());
+            recorder
+                .store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now(),
+                })
+                .unwrap();
 
-        Perfect recall m(t) = 1 results in rate of review of 0.
-        Forgotten recall m(t) = 0 results in review events at rate q^(-0.5) per unit time.
+            // The history is collected per question, the answer makes it due tomorrow.
+            let mut selector = SuperMemo2Selector::default();
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &recorder);
+            assert_eq!(selector.get_question(), None);
+            clock.advance(DAY);
+            assert_eq!(selector.get_question(), Some(question));
+        }
 
-        In the paper, this is then transformed from rate-of-reviewing to next-review-time with a
-        thinning algorithm.
-    */
+        #[test]
+        fn test_intervals() {
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let clock = test_clock();
+            let question = Question::default();
 
-    /// n_t is the forgetting rate, t is the current time, t_last_review is previous test.
-    pub fn recall(n_t: f64, t: f64, t_last_review: f64) -> f64 {
-        assert!(t_last_review <= t, "t should be greater than t_last_review");
-        ((-n_t) * (t - t_last_review)).exp()
-    }
+            let mut selector = SuperMemo2Selector::new(Default::default());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &MemoryRecorder::new());
 
-    /// Review intensity based on the recall curve and review rate.
-    pub fn review_intensity(q: f64, recall: f64) -> f64 {
-        q.powf(-0.5) * (1.0 - recall)
-    }
+            let answer = |selector: &mut SuperMemo2Selector| {
+                assert_eq!(selector.get_question(), Some(question));
+                selector.store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now(),
+                });
+                assert_eq!(selector.get_question(), None);
+            };
 
-    /// Time transform.
-    pub fn intensity(n_t: f64, t: f64, q: f64) -> f64 {
-        (1.0 / q.sqrt()) * (1.0 - (-n_t * t).exp())
-    }
+            // New question, after the first correct answer the interval is one day.
+            answer(&mut selector);
+            clock.advance(day);
+            answer(&mut selector);
 
-    /// Calculate the next time to review based on the intensity.
-    pub fn next_review_time(n_t: f64, q: f64, t_max: f64) -> Option<f64> {
-        use rand_distr::Distribution;
-        let max_interval = 1.0 / q.sqrt();
-        let exp = rand_distr::Exp::new(1.0 / max_interval).unwrap();
-        let mut t = 0.0;
-        loop {
-            let t_ = exp.sample(&mut rand::thread_rng());
-            // println!("t_: {t_}");
-            if t_ + t > t_max {
-                return None; // Beyond max scheduling interval.
-            }
-            t = t + t_;
-            let proposed_interval = intensity(n_t, t, q);
-            // println!("Proposed: {proposed_interval}");
-            if rand::random::<f64>() < (proposed_interval / t_max) {
-                return Some(t);
-            }
+            // After the second correct answer it is six days.
+            clock.advance(day * 5);
+            assert_eq!(selector.get_question(), None);
+            clock.advance(day);
+            answer(&mut selector);
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
+        #[test]
+        fn test_elapsed() {
+            let config = SuperMemo2Config::default();
+            // Two correct answers, the interval is six days.
+            let mut state = QuestionState::default();
+            state.update(5, 0.0, &config);
+            state.update(5, 1.0, &config);
+            assert_eq!(state.inter_repetition(), DAY * 6);
+
+            let next = |elapsed_days: f64, grade: u64| {
+                let mut state = state.clone();
+                state.update(grade, elapsed_days, &config);
+                state.inter_repetition()
+            };
+            let on_time = next(6.0, 5);
+            assert_eq!(on_time, DAY * 16);
+            // Overdue gives a bonus, less so if recalling took effort.
+            assert_eq!(next(12.0, 5), DAY * 32);
+            assert_eq!(next(12.0, 3), DAY * 24);
+            // An early review grows less, a review on the same day not at all.
+            assert_eq!(next(3.0, 5), DAY * 8);
+            assert_eq!(next(0.0, 5), DAY * 6);
+        }
+
+        #[test]
+        fn test_fuzz() {
+            let clock = test_clock();
+            let questions = test_questions(20);
+            let config = SuperMemo2Config {
+                fuzz: 0.1,
+                load_balance: true,
+                ..Default::default()
+            };
+            let make_selector = |recorder: &MemoryRecorder| {
+                let mut selector = SuperMemo2Selector::new(config.clone());
+                selector.set_clock(clock.clone());
+                selector.set_questions(&questions, recorder);
+                selector
+            };
+
+            // Answer all questions on two days, their interval becomes six days.
+            let mut recorder = MemoryRecorder::new();
+            let mut selector = make_selector(&recorder);
+            for _ in 0..2 {
+                while let Some(question) = selector.get_question() {
+                    let record = Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
+                    };
+                    selector.store_record(&record);
+                    recorder.store_record(&record).unwrap();
+                }
+                clock.advance(DAY);
+            }
+
+            // Instead of all on one day, they are spread over five to seven days.
+            let forecast = selector.forecast(8).unwrap();
+            assert_eq!(forecast[4..7].iter().sum::<usize>(), questions.len());
+            assert!(forecast[4..7].iter().all(|v| *v <= 7));
+
+            // Replaying the history gives the same schedule.
+            assert_eq!(make_selector(&recorder).forecast(8).unwrap(), forecast);
+        }
+
+        #[test]
+        fn test_learning_steps() {
+            let minute = std::time::Duration::from_secs(60);
+            let clock = test_clock();
+            let question = Question::default();
+
+            let mut selector = SuperMemo2Selector::new(SuperMemo2Config {
+                learning_steps_minutes: vec![1.0, 10.0],
+                relearning_steps_minutes: vec![10.0],
+                ..Default::default()
+            });
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &MemoryRecorder::new());
+
+            // Answers the question if it is asked after the time advanced.
+            let answer =
+                |selector: &mut SuperMemo2Selector, advance: std::time::Duration, score: f64| {
+                    clock.advance(advance);
+                    if selector.get_question() != Some(question) {
+                        return false;
+                    }
+                    selector.store_record(&Record {
+                        question,
+                        score,
+                        time: clock.now(),
+                    });
+                    true
+                };
+
+            // Failing a new question asks it again after the first step, succeeding after the
+            // second step.
+            assert!(answer(&mut selector, minute, 0.0));
+            assert!(!answer(&mut selector, minute / 2, 1.0));
+            assert!(answer(&mut selector, minute / 2, 1.0));
+            assert!(!answer(&mut selector, minute * 5, 1.0));
+            assert!(answer(&mut selector, minute * 5, 1.0));
+
+            // It graduated, the next review is the next day.
+            assert!(!answer(&mut selector, minute * 60, 1.0));
+            assert!(answer(&mut selector, DAY, 0.0));
+
+            // Failing the review uses the relearning step, followed by a day.
+            assert!(!answer(&mut selector, minute * 5, 1.0));
+            assert!(answer(&mut selector, minute * 5, 1.0));
+            assert!(!answer(&mut selector, minute * 60, 1.0));
+            assert!(answer(&mut selector, DAY, 1.0));
+        }
+
+        #[test]
+        fn test_explain() {
+            let clock = test_clock();
+            let question = Question::default();
+            let mut selector = SuperMemo2Selector::new(Default::default());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &MemoryRecorder::new());
+            assert_eq!(selector.explain(), None);
+
+            assert_eq!(selector.get_question(), Some(question));
+            let explanation = selector.explain().unwrap();
+            assert_eq!(explanation.pool, "new");
+            assert_eq!(explanation.overdue_seconds, None);
+
+            // Failing it puts it in the re-review pool.
+            selector.store_record(&Record {
+                question,
+                score: 0.0,
+                time: clock.now(),
+            });
+            assert_eq!(selector.get_question(), Some(question));
+            assert_eq!(selector.explain().unwrap().pool, "re-review");
+
+            // Answered well it comes back as a review, overdue if it wasn't practiced for a while.
+            selector.store_record(&Record {
+                question,
+                score: 1.0,
+                time: clock.now(),
+            });
+            assert_eq!(selector.get_question(), None);
+            assert_eq!(selector.explain(), None);
+            clock.advance(DAY * 3);
+            assert_eq!(selector.get_question(), Some(question));
+            let explanation = selector.explain().unwrap();
+            assert_eq!(explanation.pool, "due");
+            assert!(explanation.overdue_seconds.unwrap() > DAY.as_secs_f64());
+            assert!(explanation
+                .state
+                .iter()
+                .any(|(key, value)| key == "interval" && value == "1 days"));
+        }
+
+        #[test]
+        fn test_forecast_progress() {
+            let clock = test_clock();
+            let questions = [
+                Question::default(),
+                Question {
+                    learnable: LearnableId(1),
+                    ..Default::default()
+                },
+            ];
+
+            let mut selector = SuperMemo2Selector::new(Default::default());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+            assert_eq!(selector.forecast(3), Some(vec![2, 0, 0]));
+
+            // Answering one question correctly makes it due tomorrow.
+            selector.store_record(&Record {
+                question: questions[0],
+                score: 1.0,
+                time: clock.now(),
+            });
+            assert_eq!(selector.forecast(3), Some(vec![1, 1, 0]));
+            assert_eq!(
+                selector.progress(),
+                Some(Progress {
+                    new: 1,
+                    total: 2,
+                    ..Default::default()
+                })
+            );
+
+            // Failing the other one marks it for re-review, as well as due tomorrow.
+            selector.store_record(&Record {
+                question: questions[1],
+                score: 0.0,
+                time: clock.now(),
+            });
+            assert_eq!(selector.forecast(3), Some(vec![1, 1, 0]));
+            assert_eq!(
+                selector.progress(),
+                Some(Progress {
+                    re_review: 1,
+                    total: 2,
+                    ..Default::default()
+                })
+            );
+        }
+
+        #[test]
+        fn test_limits() {
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let clock = test_clock();
+            let questions = test_questions(5);
+            let config = SuperMemo2Config {
+                new_per_day: Some(2),
+                reviews_per_day: Some(1),
+                ..Default::default()
+            };
+            let mut recorder = MemoryRecorder::new();
+
+            // Answer everything that is asked, returns the number of questions asked.
+            let session = |recorder: &mut MemoryRecorder| {
+                let mut selector = SuperMemo2Selector::new(config.clone());
+                selector.set_clock(clock.clone());
+                selector.set_questions(&questions, recorder);
+                let mut asked = 0;
+                while let Some(question) = selector.get_question() {
+                    let record = Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
+                    };
+                    selector.store_record(&record);
+                    recorder.store_record(&record).unwrap();
+                    asked += 1;
+                }
+                asked
+            };
+
+            // Only two new questions on the first day, also not after a restart.
+            assert_eq!(session(&mut recorder), 2);
+            clock.advance(std::time::Duration::from_secs(60));
+            assert_eq!(session(&mut recorder), 0);
+
+            // The forecast holds back the same questions, the other new ones and the second
+            // review are spread over the next days.
+            let mut selector = SuperMemo2Selector::new(config.clone());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &recorder);
+            assert_eq!(selector.forecast(3), Some(vec![0, 3, 2]));
+
+            // Next day, one review of the two due ones and two new questions.
+            clock.advance(day);
+            assert_eq!(session(&mut recorder), 3);
+        }
+
+        #[test]
+        fn test_limits_next_day() {
+            let clock = test_clock();
+            let questions = test_questions(5);
+            let mut selector = SuperMemo2Selector::new(SuperMemo2Config {
+                new_per_day: Some(2),
+                reviews_per_day: Some(1),
+                ..Default::default()
+            });
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+
+            // Answer everything that is asked, returns the number of questions asked.
+            let mut session = || {
+                let mut asked = 0;
+                while let Some(question) = selector.get_question() {
+                    selector.store_record(&Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
+                    });
+                    asked += 1;
+                }
+                asked
+            };
+
+            // The limits start over after midnight without setting up the selector again.
+            assert_eq!(session(), 2);
+            clock.advance(DAY);
+            assert_eq!(session(), 3);
+            clock.advance(DAY);
+            assert_eq!(session(), 2);
+        }
+
+        #[test]
+        fn test_snapshot() {
+            let clock = test_clock();
+            let question = Question::default();
+            let recorder = MemoryRecorder::new();
+            let mut selector = SuperMemo2Selector::default();
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &recorder);
+            selector.store_record(&Record {
+                question,
+                score: 0.0,
+                time: clock.now(),
+            });
+            let state = selector.snapshot().unwrap();
+
+            // The snapshot is only for the same selector with the same configuration.
+            let mut other = SuperMemo2Selector::new(SuperMemo2Config {
+                new_per_day: Some(10),
+                ..Default::default()
+            });
+            assert!(other.restore(&[question], &recorder, &state).is_err());
+            let mut other = crate::algorithm::fsrs::FsrsSelector::new(Default::default());
+            assert!(other.restore(&[question], &recorder, &state).is_err());
+
+            // The re-review is kept on the same day, but not the next day.
+            let mut restored = SuperMemo2Selector::default();
+            restored.set_clock(clock.clone());
+            restored.restore(&[question], &recorder, &state).unwrap();
+            assert!(restored.questions[0].pending_re_review);
+            clock.advance(DAY);
+            restored.restore(&[question], &recorder, &state).unwrap();
+            assert!(!restored.questions[0].pending_re_review);
+        }
+
+        #[test]
+        fn test_seeded_order() {
+            let questions = test_questions(20);
+            let order = |seed: u64| {
+                let clock = Arc::new(ManualClock::new(std::time::SystemTime::UNIX_EPOCH));
+                let mut selector = SuperMemo2Selector::new(Default::default());
+                selector.set_clock(clock.clone());
+                selector.set_rng(crate::rng::SelectorRng::from_seed(seed));
+                selector.set_questions(&questions, &MemoryRecorder::new());
+                let mut res = vec![];
+                while let Some(question) = selector.get_question() {
+                    res.push(question);
+                    selector.store_record(&Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
+                    });
+                }
+                res
+            };
+            assert_eq!(order(3).len(), questions.len());
+            assert_eq!(order(3), order(3));
+            assert_ne!(order(3), order(4));
+        }
+    }
+}
+
+// Free Spaced Repetition Scheduler, as described in
+// https://github.com/open-spaced-repetition/fsrs4anki/wiki/The-Algorithm
+// This implements the FSRS-4.5 version of the model.
+pub mod fsrs {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+
+    /*
+        Summarized from the description;
+
+        Each card has a memory state consisting of the stability S (the interval in days at which
+        the probability of recall is 90%) and the difficulty D (in [1, 10]).
+
+        Retrievability, the probability of recall after t days:
+            R(t, S) = (1 + FACTOR * t / S) ^ DECAY
+
+        The next interval for a requested retention r is obtained by solving R(t, S) = r for t:
+            I(r, S) = S / FACTOR * (r ^ (1 / DECAY) - 1)
+
+        The first rating G determines the initial state:
+            S_0(G) = w[G - 1]
+            D_0(G) = w4 - (G - 3) * w5
+
+        Subsequent ratings update difficulty with a mean reversion towards D_0(4):
+            D' = w7 * D_0(4) + (1 - w7) * (D - w6 * (G - 3))
+
+        And stability after a successful recall:
+            S' = S * (exp(w8) * (11 - D) * S^(-w9) * (exp(w10 * (1 - R)) - 1) * hard * easy + 1)
+        with hard = w15 if G = 2 and easy = w16 if G = 4, or after a lapse:
+            S' = min(S, w11 * D^(-w12) * ((S + 1)^w13 - 1) * exp(w14 * (1 - R)))
+    */
+
+    const DECAY: f64 = -0.5;
+    const FACTOR: f64 = 19.0 / 81.0;
+    const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+    /// The four button rating used by FSRS.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Rating {
+        Again = 1,
+        Hard = 2,
+        Good = 3,
+        Easy = 4,
+    }
+
+    impl Rating {
+        /// Map the score onto a rating, via the SuperMemo grade. Grades below 3 are failures.
+        pub fn from_score(score: Score) -> Rating {
+            match super::super_memo_2::score_to_grade(score) {
+                0..=2 => Rating::Again,
+                3 => Rating::Hard,
+                4 => Rating::Good,
+                _ => Rating::Easy,
+            }
+        }
+
+        fn value(&self) -> f64 {
+            *self as u8 as f64
+        }
+
+        /// The next rating up, for a correct first answer that got partial credit before.
+        fn raised(self) -> Rating {
+            match self {
+                Rating::Again => Rating::Again,
+                Rating::Hard => Rating::Good,
+                Rating::Good | Rating::Easy => Rating::Easy,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct FsrsConfig {
+        /// The 17 weights of the model, w0 through w16.
+        pub weights: Vec<f64>,
+        /// The probability of recall at which a question becomes due again, in (0.0, 1.0).
+        pub request_retention: f64,
+        /// Maximum interval in days.
+        pub maximum_interval: f64,
+        /// Maximum number of new questions per day, no limit if None.
+        #[serde(default)]
+        pub new_per_day: Option<usize>,
+    }
+
+    impl Default for FsrsConfig {
+        fn default() -> FsrsConfig {
+            FsrsConfig {
+                weights: vec![
+                    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367,
+                    1.0461, 2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+                ],
+                request_retention: 0.9,
+                maximum_interval: 36500.0,
+                new_per_day: None,
+            }
+        }
+    }
+
+    /// The memory state of a single question.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    pub struct MemoryState {
+        /// Interval in days at which the recall probability is 90%.
+        pub stability: f64,
+        /// Difficulty, in [1.0, 10.0].
+        pub difficulty: f64,
+    }
+
+    impl FsrsConfig {
+        /// Probability of recall after elapsed_days for a given stability.
+        pub fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+            (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+        }
+
+        fn initial_difficulty(&self, rating: Rating) -> f64 {
+            let w = &self.weights;
+            (w[4] - (rating.value() - 3.0) * w[5]).clamp(1.0, 10.0)
+        }
+
+        /// The state after the very first review of a question.
+        pub fn initial_state(&self, rating: Rating) -> MemoryState {
+            MemoryState {
+                stability: self.weights[rating as usize - 1].max(0.1),
+                difficulty: self.initial_difficulty(rating),
+            }
+        }
+
+        /// The state after a review that happened elapsed_days after the previous one.
+        pub fn next_state(
+            &self,
+            state: &MemoryState,
+            elapsed_days: f64,
+            rating: Rating,
+        ) -> MemoryState {
+            let w = &self.weights;
+            let s = state.stability;
+            let d = state.difficulty;
+            let r = Self::retrievability(elapsed_days, s);
+
+            let difficulty = d - w[6] * (rating.value() - 3.0);
+            let difficulty =
+                w[7] * self.initial_difficulty(Rating::Easy) + (1.0 - w[7]) * difficulty;
+            let difficulty = difficulty.clamp(1.0, 10.0);
+
+            let stability = if rating == Rating::Again {
+                let forget = w[11]
+                    * d.powf(-w[12])
+                    * ((s + 1.0).powf(w[13]) - 1.0)
+                    * (w[14] * (1.0 - r)).exp();
+                forget.min(s)
+            } else {
+                let hard_penalty = if rating == Rating::Hard { w[15] } else { 1.0 };
+                let easy_bonus = if rating == Rating::Easy { w[16] } else { 1.0 };
+                s * (w[8].exp()
+                    * (11.0 - d)
+                    * s.powf(-w[9])
+                    * ((w[10] * (1.0 - r)).exp() - 1.0)
+                    * hard_penalty
+                    * easy_bonus
+                    + 1.0)
+            };
+
+            MemoryState {
+                stability: stability.max(0.1),
+                difficulty,
+            }
+        }
+
+        /// Interval in whole days after which the recall drops to the requested retention.
+        pub fn next_interval(&self, stability: f64) -> f64 {
+            let interval = stability / FACTOR * (self.request_retention.powf(1.0 / DECAY) - 1.0);
+            interval.round().clamp(1.0, self.maximum_interval)
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    struct QuestionInfo {
+        /// The question itself.
+        question: Question,
+
+        /// Last time this question was asked.
+        last_time: std::time::SystemTime,
+
+        /// The memory state, None if this question was never answered.
+        state: Option<MemoryState>,
+
+        /// Time of the first answer, for the daily limit on new questions.
+        #[serde(default)]
+        introduced: Option<std::time::SystemTime>,
+
+        /// Interval in days until the next review.
+        interval: f64,
+
+        /// If question was asked and the last rating was below good.
+        pending_re_review: bool,
+
+        /// Not due before this moment, from partial credit for answers to related questions.
+        /// Cleared by an answer.
+        postponed: Option<std::time::SystemTime>,
+
+        /// Whether this question got partial credit before its first answer, which then counts
+        /// for one rating more if it is correct. Cleared by an answer.
+        #[serde(default)]
+        primed: bool,
+    }
+
+    impl QuestionInfo {
+        /// The moment this question is due for review, None if it was never answered.
+        fn due_time(&self, boundary: &crate::clock::DayBoundary) -> Option<std::time::SystemTime> {
+            self.state.as_ref()?;
+            // See DayBoundary::due_after, this snaps to the start of a day if configured.
+            let interval = std::time::Duration::from_secs_f64(self.interval * SECONDS_PER_DAY);
+            let due = boundary.due_after(self.last_time, interval);
+            Some(self.postponed.map(|p| p.max(due)).unwrap_or(due))
+        }
+
+        fn update(&mut self, config: &FsrsConfig, record: &Record) {
+            let rating = Rating::from_score(record.score);
+            let elapsed_days = record
+                .time
+                .duration_since(self.last_time)
+                .unwrap_or_default()
+                .as_secs_f64()
+                / SECONDS_PER_DAY;
+            let state = match &self.state {
+                Some(state) => config.next_state(state, elapsed_days, rating),
+                None if self.primed => config.initial_state(rating.raised()),
+                None => config.initial_state(rating),
+            };
+            self.interval = config.next_interval(state.stability);
+            self.state = Some(state);
+            self.introduced.get_or_insert(record.time);
+            self.last_time = record.time;
+            self.postponed = None;
+            self.primed = false;
+        }
+    }
+
+    /// A selector that implements the Free Spaced Repetition Scheduler.
+    #[derive(Debug)]
+    pub struct FsrsSelector {
+        questions: Vec<QuestionInfo>,
+        clock: std::sync::Arc<dyn Clock>,
+        rng: crate::rng::SelectorRng,
+        config: FsrsConfig,
+        day_boundary: crate::clock::DayBoundary,
+        /// Why the last question was chosen.
+        last_explanation: Option<Explanation>,
+    }
+    impl FsrsSelector {
+        pub fn new(config: FsrsConfig) -> Self {
+            FsrsSelector {
+                questions: vec![],
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                rng: Default::default(),
+                config,
+                day_boundary: Default::default(),
+                last_explanation: None,
+            }
+        }
+
+        /// Number of questions answered for the first time on the day of now.
+        fn new_today(&self, now: std::time::SystemTime) -> usize {
+            self.questions
+                .iter()
+                .filter_map(|z| z.introduced)
+                .filter(|t| self.day_boundary.same_day(*t, now))
+                .count()
+        }
+
+        /// Explanation for choosing the question from the pool.
+        fn explanation(&self, question: &Question, pool: &str) -> Option<Explanation> {
+            let z = self.questions.iter().find(|z| z.question == *question)?;
+            let now = self.clock.now();
+            let mut state = vec![];
+            if let Some(memory) = z.state.as_ref() {
+                state.push(("stability".to_owned(), format!("{:.2}", memory.stability)));
+                state.push(("difficulty".to_owned(), format!("{:.2}", memory.difficulty)));
+                state.push(("interval".to_owned(), format!("{:.1} days", z.interval)));
+            }
+            Some(Explanation {
+                question: *question,
+                pool: pool.to_owned(),
+                overdue_seconds: z
+                    .due_time(&self.day_boundary)
+                    .map(|t| super::seconds_between(t, now)),
+                recall: self.predict_recall(question, now),
+                state,
+            })
+        }
+    }
+
+    impl Selector for FsrsSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock;
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.rng = rng;
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.day_boundary = boundary;
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        /// Replay the answers and the partial credit per question in the order they were given.
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            self.questions.clear();
+            let now = self.clock.now();
+            for question in questions.iter() {
+                let records = recorder
+                    .get_records_by_question(question)
+                    .expect("Should return empty if unknown");
+
+                self.questions.push(QuestionInfo {
+                    question: *question,
+                    last_time: now,
+                    state: None,
+                    introduced: None,
+                    interval: 0.0,
+                    pending_re_review: false,
+                    postponed: None,
+                    primed: false,
+                });
+                let mut credit = credit.iter().filter(|c| c.question == *question).peekable();
+                for record in records.iter() {
+                    while let Some(c) = credit.next_if(|c| c.time <= record.time) {
+                        self.store_partial_credit(c);
+                    }
+                    let z = self.questions.last_mut().expect("Just pushed");
+                    z.update(&self.config, record);
+                }
+                for c in credit {
+                    self.store_partial_credit(c);
+                }
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve a question to ask. Questions that were never answered are due immediately,
+        /// up to the daily limit, after that a question is due if its interval has passed,
+        /// followed by re-reviews of the questions that were not rated good or easy this session.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            use rand::seq::SliceRandom;
+            self.last_explanation = None;
+            let now = self.clock.now();
+            let may_ask_new = self
+                .config
+                .new_per_day
+                .map(|limit| self.new_today(now) < limit)
+                .unwrap_or(true);
+            let questions_pending_review = self
+                .questions
+                .iter()
+                .filter(|z| {
+                    z.due_time(&self.day_boundary)
+                        .map(|t| now >= t)
+                        .unwrap_or(may_ask_new)
+                })
+                .filter(|z| allowed(&z.question))
+                .collect::<Vec<_>>();
+            if !questions_pending_review.is_empty() {
+                let question = questions_pending_review
+                    .choose(&mut self.rng)
+                    .unwrap()
+                    .question;
+                let is_new = self
+                    .questions
+                    .iter()
+                    .any(|z| z.question == question && z.state.is_none());
+                let pool = if is_new { "new" } else { "due" };
+                self.last_explanation = self.explanation(&question, pool);
+                return Some(question);
+            }
+
+            let questions_pending_re_review = self
+                .questions
+                .iter()
+                .filter(|z| z.pending_re_review && allowed(&z.question))
+                .collect::<Vec<_>>();
+            if !questions_pending_re_review.is_empty() {
+                let question = questions_pending_re_review
+                    .choose(&mut self.rng)
+                    .unwrap()
+                    .question;
+                self.last_explanation = self.explanation(&question, "re-review");
+                return Some(question);
+            }
+
+            None
+        }
+
+        /// Store answer to a question.
+        fn store_record(&mut self, record: &Record) {
+            let z = self
+                .questions
+                .iter_mut()
+                .find(|v| v.question == record.question)
+                .expect("Passed question for which we don't have a record.");
+            z.pending_re_review = Rating::from_score(record.score) < Rating::Good;
+            z.update(&self.config, record);
+        }
+
+        /// Postpone an answered question, the memory state is unaffected. A new question is
+        /// primed instead if the credit counts as a correct response.
+        fn store_partial_credit(&mut self, record: &Record) {
+            let boundary = &self.day_boundary;
+            let Some(z) = self
+                .questions
+                .iter_mut()
+                .find(|v| v.question == record.question)
+            else {
+                return;
+            };
+            let Some(due) = z.due_time(boundary) else {
+                z.primed |= Rating::from_score(record.score) > Rating::Again;
+                return;
+            };
+            if z.pending_re_review {
+                return;
+            }
+            let postponed =
+                super::partial_credit_postpone(boundary, z.interval, record.score, record.time);
+            if postponed > due {
+                z.postponed = Some(postponed);
+            }
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            let z = self.questions.iter().find(|v| v.question == *question)?;
+            let state = z.state.as_ref()?;
+            let elapsed_days = at_time
+                .duration_since(z.last_time)
+                .unwrap_or_default()
+                .as_secs_f64()
+                / SECONDS_PER_DAY;
+            Some(FsrsConfig::retrievability(elapsed_days, state.stability))
+        }
+
+        /// Forecast with the new questions capped by the daily limit.
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            let now = self.clock.now();
+            let new = self.questions.iter().filter(|z| z.state.is_none());
+            let new = super::limited_due_per_day(
+                now,
+                new.map(|_| now),
+                self.config.new_per_day,
+                self.new_today(now),
+                days,
+            );
+            let due_times = self.questions.iter().filter_map(|z| {
+                if z.pending_re_review {
+                    Some(now)
+                } else {
+                    z.due_time(&self.day_boundary)
+                }
+            });
+            Some(super::add_forecasts(
+                new,
+                super::due_per_day(now, due_times, days),
+            ))
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            let now = self.clock.now();
+            let mut progress = Progress {
+                total: self.questions.len(),
+                ..Default::default()
+            };
+            for z in self.questions.iter() {
+                match z.due_time(&self.day_boundary) {
+                    None => progress.new += 1,
+                    Some(t) if now >= t => progress.due += 1,
+                    _ if z.pending_re_review => progress.re_review += 1,
+                    _ => {}
+                }
+                if z.state.is_some() && z.interval >= super::MATURE_INTERVAL_DAYS {
+                    progress.mature += 1;
+                }
+            }
+            Some(progress)
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.last_explanation.clone()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            let config = (&self.config, &self.day_boundary);
+            super::snapshot_tagged("Fsrs", &config, &self.questions)
+        }
+
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            _recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let infos: Vec<QuestionInfo> =
+                super::restore_tagged("Fsrs", &(&self.config, &self.day_boundary), state)?;
+            super::check_snapshot_questions(questions, infos.iter().map(|z| z.question))?;
+            self.questions = infos;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::{test_clock, test_questions};
+
+        #[test]
+        fn test_stability() {
+            let config = FsrsConfig::default();
+            let state = config.initial_state(Rating::Good);
+            assert_eq!(state.stability, config.weights[2]);
+
+            // At the requested retention of 0.9 the interval equals the stability.
+            assert_eq!(
+                config.next_interval(state.stability),
+                state.stability.round()
+            );
+
+            // Recalling a question when it is due increases the stability, forgetting it reduces
+            // it and makes the question more difficult.
+            let good = config.next_state(&state, state.stability, Rating::Good);
+            assert!(good.stability > state.stability);
+            let again = config.next_state(&state, state.stability, Rating::Again);
+            assert!(again.stability < state.stability);
+            assert!(again.difficulty > good.difficulty);
+
+            // Reviewing immediately barely changes the stability.
+            let immediate = config.next_state(&state, 0.0, Rating::Good);
+            assert!((immediate.stability - state.stability).abs() < 1e-6);
+        }
+
+        #[test]
+        fn test_new_per_day() {
+            use crate::recorder::MemoryRecorder;
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let clock = test_clock();
+            let questions = test_questions(3);
+            let mut selector = FsrsSelector::new(FsrsConfig {
+                new_per_day: Some(2),
+                ..Default::default()
+            });
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+            assert_eq!(selector.forecast(3), Some(vec![2, 1, 0]));
+
+            // Two new questions are asked today, the third one tomorrow.
+            for _ in 0..2 {
+                let question = selector.get_question().unwrap();
+                selector.store_record(&Record {
+                    question,
+                    score: 0.8,
+                    time: clock.now(),
+                });
+            }
+            assert_eq!(selector.get_question(), None);
+            assert_eq!(selector.forecast(3), Some(vec![0, 1, 0]));
+            clock.advance(day);
+            assert!(selector.get_question().is_some());
+        }
+
+        #[test]
+        fn test_selector() {
+            use crate::recorder::MemoryRecorder;
+            let hour = std::time::Duration::from_secs(60 * 60);
+            let clock = test_clock();
+
+            assert_eq!(Rating::from_score(0.0), Rating::Again);
+            assert_eq!(Rating::from_score(0.4), Rating::Again);
+            assert_eq!(Rating::from_score(0.6), Rating::Hard);
+            assert_eq!(Rating::from_score(0.8), Rating::Good);
+            assert_eq!(Rating::from_score(1.0), Rating::Easy);
+
+            let question = Question::default();
+            let mut selector = FsrsSelector::new(Default::default());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[question], &MemoryRecorder::new());
+            let answer = |selector: &mut FsrsSelector, score: Score| {
+                assert_eq!(selector.get_question(), Some(question));
+                selector.store_record(&Record {
+                    question,
+                    score,
+                    time: clock.now(),
+                });
+            };
+
+            // A hard answer is asked again this session, until it is rated good. Then the session
+            // ends, and the question is due after the one day interval minus the slack of the
+            // rolling day boundary.
+            answer(&mut selector, 0.6);
+            answer(&mut selector, 0.8);
+            assert_eq!(selector.get_question(), None);
+            assert_eq!(selector.forecast(3), Some(vec![0, 1, 0]));
+            clock.advance(hour * 17);
+            assert_eq!(selector.get_question(), None);
+            clock.advance(hour);
+
+            // Easy when due increases the interval, the question isn't due for days.
+            answer(&mut selector, 1.0);
+            assert_eq!(selector.get_question(), None);
+            let forecast = selector.forecast(30).unwrap();
+            assert_eq!(forecast[..3], [0, 0, 0]);
+            assert_eq!(forecast.iter().sum::<usize>(), 1);
+
+            // Forgetting it is asked again this session, and the interval is short again.
+            let due = forecast.iter().position(|v| *v == 1).unwrap() as u32;
+            clock.advance(hour * 24 * due);
+            answer(&mut selector, 0.0);
+            answer(&mut selector, 0.8);
+            assert_eq!(selector.get_question(), None);
+            assert!(
+                selector.forecast(30).unwrap().iter().position(|v| *v == 1) < Some(due as usize)
+            );
+        }
+    }
+}
+
+// The Leitner system, as used with physical flashcards.
+// https://en.wikipedia.org/wiki/Leitner_system
+pub mod leitner {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+
+    /*
+        Cards are kept in a number of boxes, each box has a review interval that is longer than
+        the one before it. All cards start in the first box. When a card is recalled successfully
+        it moves up to the next box, when it is not it moves back to the first box (or down one
+        box if so configured).
+    */
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct LeitnerConfig {
+        /// The review interval in days for each box, the number of boxes is the length of this.
+        pub intervals: Vec<u64>,
+        /// If true a failed question moves back to the first box, otherwise down by one box.
+        pub demote_to_first: bool,
+        /// Maximum number of new questions per day, no limit if None.
+        #[serde(default)]
+        pub new_per_day: Option<usize>,
+    }
+
+    impl Default for LeitnerConfig {
+        fn default() -> LeitnerConfig {
+            LeitnerConfig {
+                intervals: vec![1, 2, 4, 8, 16, 32],
+                demote_to_first: true,
+                new_per_day: None,
+            }
+        }
+    }
+
+    impl LeitnerConfig {
+        /// The box a question moves to after it was answered with the provided score.
+        pub fn next_box(&self, current: usize, score: Score) -> usize {
+            // Same definition of a successful recall as SuperMemo; a grade of 3 or more.
+            if super::super_memo_2::score_to_grade(score) >= 3 {
+                (current + 1).min(self.intervals.len().saturating_sub(1))
+            } else if self.demote_to_first {
+                0
+            } else {
+                current.saturating_sub(1)
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    struct QuestionInfo {
+        /// The question itself.
+        question: Question,
+
+        /// Last time this question was asked, None if it was never answered.
+        last_time: Option<std::time::SystemTime>,
+
+        /// Time of the first answer, for the daily limit on new questions.
+        #[serde(default)]
+        introduced: Option<std::time::SystemTime>,
+
+        /// The box this question is in.
+        current_box: usize,
+    }
+
+    impl QuestionInfo {
+        /// The moment this question is due for review, None if it was never answered.
+        fn due_time(
+            &self,
+            config: &LeitnerConfig,
+            boundary: &crate::clock::DayBoundary,
+        ) -> Option<std::time::SystemTime> {
+            // See DayBoundary::due_after, this snaps to the start of a day if configured.
+            self.last_time
+                .map(|t| boundary.days_after(t, config.intervals[self.current_box]))
+        }
+    }
+
+    /// A selector that implements the Leitner system.
+    #[derive(Debug)]
+    pub struct LeitnerSelector {
+        questions: Vec<QuestionInfo>,
+        clock: std::sync::Arc<dyn Clock>,
+        rng: crate::rng::SelectorRng,
+        config: LeitnerConfig,
+        day_boundary: crate::clock::DayBoundary,
+        /// Why the last question was chosen.
+        last_explanation: Option<Explanation>,
+    }
+    impl LeitnerSelector {
+        /// Create the selector, fails if the configuration has no boxes.
+        pub fn new(config: LeitnerConfig) -> Result<Self, MemorizerError> {
+            if config.intervals.is_empty() {
+                return Err("Leitner system needs at least one box".into());
+            }
+            Ok(LeitnerSelector {
+                questions: vec![],
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                rng: Default::default(),
+                config,
+                day_boundary: Default::default(),
+                last_explanation: None,
+            })
+        }
+
+        /// Number of questions answered for the first time on the day of now.
+        fn new_today(&self, now: std::time::SystemTime) -> usize {
+            self.questions
+                .iter()
+                .filter_map(|z| z.introduced)
+                .filter(|t| self.day_boundary.same_day(*t, now))
+                .count()
+        }
+
+        /// Explanation for choosing the question.
+        fn explanation(&self, question: &Question) -> Option<Explanation> {
+            let z = self.questions.iter().find(|z| z.question == *question)?;
+            let due_time = z.due_time(&self.config, &self.day_boundary);
+            Some(Explanation {
+                question: *question,
+                pool: if due_time.is_none() { "new" } else { "due" }.to_owned(),
+                overdue_seconds: due_time.map(|t| super::seconds_between(t, self.clock.now())),
+                recall: None,
+                state: vec![
+                    ("box".to_owned(), z.current_box.to_string()),
+                    (
+                        "interval".to_owned(),
+                        format!("{} days", self.config.intervals[z.current_box]),
+                    ),
+                ],
+            })
+        }
+    }
+
+    impl Selector for LeitnerSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock;
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.rng = rng;
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.day_boundary = boundary;
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.questions.clear();
+            for question in questions.iter() {
+                let records = recorder
+                    .get_records_by_question(question)
+                    .expect("Should return empty if unknown");
+
+                let mut current_box = 0;
+                let mut last_time = None;
+                for record in records.iter() {
+                    current_box = self.config.next_box(current_box, record.score);
+                    last_time = Some(record.time);
+                }
+
+                self.questions.push(QuestionInfo {
+                    question: *question,
+                    last_time,
+                    introduced: records.first().map(|r| r.time),
+                    current_box,
+                });
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve a question to ask, at random from the questions whose box interval has passed
+        /// since they were last answered. Questions never answered are due up to the daily limit.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            use rand::seq::SliceRandom;
+            self.last_explanation = None;
+            let now = self.clock.now();
+            let may_ask_new = self
+                .config
+                .new_per_day
+                .map(|limit| self.new_today(now) < limit)
+                .unwrap_or(true);
+            let questions_pending_review = self
+                .questions
+                .iter()
+                .filter(|z| {
+                    z.due_time(&self.config, &self.day_boundary)
+                        .map(|t| now >= t)
+                        .unwrap_or(may_ask_new)
+                })
+                .filter(|z| allowed(&z.question))
+                .collect::<Vec<_>>();
+
+            // Reached the end of the session if nothing is due.
+            let question = questions_pending_review
+                .choose(&mut self.rng)
+                .map(|z| z.question)?;
+            self.last_explanation = self.explanation(&question);
+            Some(question)
+        }
+
+        /// Store answer to a question.
+        fn store_record(&mut self, record: &Record) {
+            let z = self
+                .questions
+                .iter_mut()
+                .find(|v| v.question == record.question)
+                .expect("Passed question for which we don't have a record.");
+            z.current_box = self.config.next_box(z.current_box, record.score);
+            z.last_time = Some(record.time);
+            z.introduced.get_or_insert(record.time);
+        }
+
+        /// Forecast with the new questions capped by the daily limit.
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            let now = self.clock.now();
+            let new = self.questions.iter().filter(|z| z.last_time.is_none());
+            let new = super::limited_due_per_day(
+                now,
+                new.map(|_| now),
+                self.config.new_per_day,
+                self.new_today(now),
+                days,
+            );
+            let due_times = self
+                .questions
+                .iter()
+                .filter_map(|z| z.due_time(&self.config, &self.day_boundary));
+            Some(super::add_forecasts(
+                new,
+                super::due_per_day(now, due_times, days),
+            ))
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            let now = self.clock.now();
+            let mut progress = Progress {
+                total: self.questions.len(),
+                ..Default::default()
+            };
+            for z in self.questions.iter() {
+                match z.due_time(&self.config, &self.day_boundary) {
+                    None => progress.new += 1,
+                    Some(t) if now >= t => progress.due += 1,
+                    _ => {}
+                }
+                if z.last_time.is_some()
+                    && self.config.intervals[z.current_box] as f64 >= super::MATURE_INTERVAL_DAYS
+                {
+                    progress.mature += 1;
+                }
+            }
+            Some(progress)
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.last_explanation.clone()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            let config = (&self.config, &self.day_boundary);
+            super::snapshot_tagged("Leitner", &config, &self.questions)
+        }
+
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            _recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let infos: Vec<QuestionInfo> =
+                super::restore_tagged("Leitner", &(&self.config, &self.day_boundary), state)?;
+            super::check_snapshot_questions(questions, infos.iter().map(|z| z.question))?;
+            self.questions = infos;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::{test_clock, test_questions};
+
+        #[test]
+        fn test_next_box() {
+            let mut config = LeitnerConfig {
+                intervals: vec![1, 3, 7],
+                demote_to_first: true,
+                ..Default::default()
+            };
+            assert_eq!(config.next_box(0, 1.0), 1);
+            assert_eq!(config.next_box(1, 0.6), 2);
+            // Can't move beyond the last box.
+            assert_eq!(config.next_box(2, 1.0), 2);
+            assert_eq!(config.next_box(2, 0.4), 0);
+
+            config.demote_to_first = false;
+            assert_eq!(config.next_box(2, 0.0), 1);
+            assert_eq!(config.next_box(0, 0.0), 0);
+        }
+
+        #[test]
+        fn test_session_ends() {
+            use crate::recorder::MemoryRecorder;
+            assert!(LeitnerSelector::new(LeitnerConfig {
+                intervals: vec![],
+                ..Default::default()
+            })
+            .is_err());
+
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let clock = test_clock();
+            let questions = test_questions(3);
+            let mut selector = LeitnerSelector::new(Default::default()).unwrap();
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+
+            // Each question is asked once, failed ones too as they go back to the first box.
+            for score in [1.0, 0.0, 1.0] {
+                let question = selector.get_question().unwrap();
+                selector.store_record(&Record {
+                    question,
+                    score,
+                    time: clock.now(),
+                });
+            }
+            assert_eq!(selector.get_question(), None);
+            clock.advance(day);
+            assert!(selector.get_question().is_some());
+        }
+
+        #[test]
+        fn test_new_per_day() {
+            use crate::recorder::MemoryRecorder;
+            let clock = test_clock();
+            let questions = test_questions(3);
+            let mut selector = LeitnerSelector::new(LeitnerConfig {
+                new_per_day: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+            assert_eq!(selector.forecast(3), Some(vec![2, 1, 0]));
+
+            // Two new questions are asked today and move to the box of two days, the third one is
+            // asked tomorrow.
+            for _ in 0..2 {
+                let question = selector.get_question().unwrap();
+                selector.store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now(),
+                });
+            }
+            assert_eq!(selector.get_question(), None);
+            assert_eq!(selector.forecast(3), Some(vec![0, 1, 2]));
+        }
+    }
+}
+
+// Half-life regression, as used by Duolingo.
+// A Trainable Spaced Repetition Model for Language Learning, Settles & Meeder, 2016.
+// https://github.com/duolingo/halflife-regression
+pub mod half_life_regression {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    /*
+        Summarized from the paper;
+
+        The probability of recall p after a lag of d days follows an exponential curve with
+        half-life h:
+            p = 2 ^ (-d / h)
+
+        The half-life is estimated from a feature vector x with weights theta:
+            h = 2 ^ (theta . x)
+
+        Here the features are a bias, sqrt(1 + correct), sqrt(1 + incorrect), an indicator for the
+        transform and an indicator for the learnable of the question. Correct and incorrect are
+        accumulated from the scores, so a score of 0.8 counts 0.8 correct and 0.2 incorrect.
+
+        Weights are trained with stochastic gradient descent on the loss:
+            (p - p_hat)^2 + alpha * (log2(h) - log2(h_hat))^2 + lambda * |theta|^2
+        where the observed half-life is h = -d / log2(p). The half-life term is taken in log space
+        instead of the linear space used in the paper, this keeps the gradient well behaved with
+        the large half-lives of mature questions.
+    */
+
+    const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+    /// Accumulated performance on a question, the input to the regression.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct RecallHistory {
+        /// Sum of the scores.
+        pub correct: f64,
+        /// Sum of one minus the scores.
+        pub incorrect: f64,
+    }
+
+    impl RecallHistory {
+        pub fn update(&mut self, score: Score) {
+            self.correct += score;
+            self.incorrect += 1.0 - score;
+        }
+    }
+
+    /// The weights of the regression.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct HalfLifeRegressionWeights {
+        pub bias: f64,
+        pub correct: f64,
+        pub incorrect: f64,
+        /// Weight per transform, captures the difficulty of the transformation.
+        #[serde(default)]
+        pub transform: HashMap<TransformId, f64>,
+        /// Weight per learnable, captures the difficulty of the learnable.
+        #[serde(default)]
+        pub learnable: HashMap<LearnableId, f64>,
+    }
+
+    impl Default for HalfLifeRegressionWeights {
+        fn default() -> HalfLifeRegressionWeights {
+            HalfLifeRegressionWeights {
+                bias: 0.0,
+                correct: 1.5,
+                incorrect: -0.5,
+                transform: Default::default(),
+                learnable: Default::default(),
+            }
+        }
+    }
+
+    impl HalfLifeRegressionWeights {
+        fn count_features(history: &RecallHistory) -> [f64; 3] {
+            [
+                1.0,
+                (1.0 + history.correct).sqrt(),
+                (1.0 + history.incorrect).sqrt(),
+            ]
+        }
+
+        /// Log2 of the half-life, theta . x.
+        fn log_half_life(&self, question: &Question, history: &RecallHistory) -> f64 {
+            let x = Self::count_features(history);
+            self.bias * x[0]
+                + self.correct * x[1]
+                + self.incorrect * x[2]
+                + self.transform.get(&question.transform).unwrap_or(&0.0)
+                + self.learnable.get(&question.learnable).unwrap_or(&0.0)
+        }
+
+        /// The estimated half-life in days.
+        pub fn half_life(
+            &self,
+            question: &Question,
+            history: &RecallHistory,
+            config: &HalfLifeRegressionConfig,
+        ) -> f64 {
+            2.0f64
+                .powf(self.log_half_life(question, history))
+                .clamp(config.min_half_life, config.max_half_life)
+        }
+
+        /// Predicted probability of recall after elapsed_days.
+        pub fn predict_recall(
+            &self,
+            question: &Question,
+            history: &RecallHistory,
+            elapsed_days: f64,
+            config: &HalfLifeRegressionConfig,
+        ) -> f64 {
+            2.0f64.powf(-elapsed_days / self.half_life(question, history, config))
+        }
+
+        /// Take a single gradient step for a question answered with score after elapsed_days.
+        fn step(
+            &mut self,
+            question: &Question,
+            history: &RecallHistory,
+            elapsed_days: f64,
+            score: Score,
+            config: &HalfLifeRegressionConfig,
+        ) {
+            let ln2 = std::f64::consts::LN_2;
+            let p = score.clamp(0.0001, 0.9999);
+            let h = (-elapsed_days / p.log2()).clamp(config.min_half_life, config.max_half_life);
+            let h_hat = self.half_life(question, history, config);
+            let p_hat = 2.0f64.powf(-elapsed_days / h_hat);
+
+            // Derivative of the loss with respect to theta . x, the same for each feature.
+            let dl_recall = 2.0 * (p_hat - p) * ln2 * ln2 * p_hat * (elapsed_days / h_hat);
+            let dl_half_life = 2.0 * config.half_life_loss_weight * (h_hat.log2() - h.log2());
+            let dl = dl_recall + dl_half_life;
+
+            let rate = config.learning_rate;
+            let lambda = config.l2_regularization;
+            let x = Self::count_features(history);
+            self.bias -= rate * (dl * x[0] + 2.0 * lambda * self.bias);
+            self.correct -= rate * (dl * x[1] + 2.0 * lambda * self.correct);
+            self.incorrect -= rate * (dl * x[2] + 2.0 * lambda * self.incorrect);
+            let t = self.transform.entry(question.transform).or_default();
+            *t -= rate * (dl + 2.0 * lambda * *t);
+            let l = self.learnable.entry(question.learnable).or_default();
+            *l -= rate * (dl + 2.0 * lambda * *l);
+        }
+
+        /// Train the weights on the provided records.
+        pub fn train(&mut self, records: &[Record], config: &HalfLifeRegressionConfig) {
+            let grouped = crate::optimize::records_by_question(records);
+            for _ in 0..config.epochs {
+                for records in grouped.iter() {
+                    let mut history = RecallHistory::default();
+                    let mut last_time = None;
+                    for record in records.iter() {
+                        if let Some(last_time) = last_time {
+                            let elapsed_days = record
+                                .time
+                                .duration_since(last_time)
+                                .unwrap_or_default()
+                                .as_secs_f64()
+                                / SECONDS_PER_DAY;
+                            self.step(
+                                &record.question,
+                                &history,
+                                elapsed_days,
+                                record.score,
+                                config,
+                            );
+                        }
+                        history.update(record.score);
+                        last_time = Some(record.time);
+                    }
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct HalfLifeRegressionConfig {
+        /// Initial weights, used as is if not training.
+        pub weights: HalfLifeRegressionWeights,
+        /// Train the weights on the full history of the recorder when the questions are set.
+        pub train_on_history: bool,
+        /// Questions with a predicted recall below this are due.
+        pub recall_threshold: f64,
+        /// Lower bound on the half-life, in days.
+        pub min_half_life: f64,
+        /// Upper bound on the half-life, in days.
+        pub max_half_life: f64,
+        /// Step size of the gradient descent.
+        pub learning_rate: f64,
+        /// Weight of the half-life term in the loss, alpha.
+        pub half_life_loss_weight: f64,
+        /// Weight of the l2 regularization term in the loss, lambda.
+        pub l2_regularization: f64,
+        /// Number of passes over the records when training.
+        pub epochs: usize,
+    }
+
+    impl Default for HalfLifeRegressionConfig {
+        fn default() -> HalfLifeRegressionConfig {
+            HalfLifeRegressionConfig {
+                weights: Default::default(),
+                train_on_history: true,
+                recall_threshold: 0.5,
+                // Same bounds as the paper, 15 minutes to 9 months.
+                min_half_life: 15.0 / (60.0 * 24.0),
+                max_half_life: 274.0,
+                learning_rate: 0.001,
+                half_life_loss_weight: 0.01,
+                l2_regularization: 0.1,
+                epochs: 10,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct QuestionInfo {
+        /// The question itself.
+        question: Question,
+
+        /// Last time this question was asked, None if it was never answered.
+        last_time: Option<std::time::SystemTime>,
+
+        /// The performance on this question.
+        history: RecallHistory,
+    }
+
+    /// A selector that asks the question with the lowest predicted recall, if that is below the
+    /// threshold.
+    #[derive(Debug)]
+    pub struct HalfLifeRegressionSelector {
+        questions: Vec<QuestionInfo>,
+        clock: std::sync::Arc<dyn Clock>,
+        weights: HalfLifeRegressionWeights,
+        config: HalfLifeRegressionConfig,
+    }
+    impl HalfLifeRegressionSelector {
+        pub fn new(config: HalfLifeRegressionConfig) -> Self {
+            HalfLifeRegressionSelector {
+                questions: vec![],
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                weights: config.weights.clone(),
+                config,
+            }
+        }
+
+        /// The weights currently in use.
+        pub fn weights(&self) -> &HalfLifeRegressionWeights {
+            &self.weights
+        }
+    }
+
+    impl Selector for HalfLifeRegressionSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock;
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.questions.clear();
+            self.weights = self.config.weights.clone();
+            if self.config.train_on_history {
+                let records = super::history(recorder, questions);
+                self.weights.train(&records, &self.config);
+            }
+
+            for question in questions.iter() {
+                let records = recorder
+                    .get_records_by_question(question)
+                    .expect("Should return empty if unknown");
+
+                let mut history = RecallHistory::default();
+                let mut last_time = None;
+                for record in records.iter() {
+                    history.update(record.score);
+                    last_time = Some(record.time);
+                }
+
+                self.questions.push(QuestionInfo {
+                    question: *question,
+                    last_time,
+                    history,
+                });
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve the question with the lowest predicted recall, questions that were never
+        /// answered come first. Returns None if all predicted recalls exceed the threshold.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            let now = self.clock.now();
+            self.questions
+                .iter()
+                .filter(|z| allowed(&z.question))
+                .map(|z| {
+                    let recall = if let Some(last_time) = z.last_time {
+                        let elapsed_days = now
+                            .duration_since(last_time)
+                            .unwrap_or_default()
+                            .as_secs_f64()
+                            / SECONDS_PER_DAY;
+                        self.weights.predict_recall(
+                            &z.question,
+                            &z.history,
+                            elapsed_days,
+                            &self.config,
+                        )
+                    } else {
+                        0.0
+                    };
+                    (z.question, recall)
+                })
+                .filter(|(_, recall)| *recall < self.config.recall_threshold)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(question, _)| question)
+        }
+
+        /// Store answer to a question.
+        fn store_record(&mut self, record: &Record) {
+            let z = self
+                .questions
+                .iter_mut()
+                .find(|v| v.question == record.question)
+                .expect("Passed question for which we don't have a record.");
+            if let Some(last_time) = z.last_time {
+                let elapsed_days = record
+                    .time
+                    .duration_since(last_time)
+                    .unwrap_or_default()
+                    .as_secs_f64()
+                    / SECONDS_PER_DAY;
+                // Keep learning from the answers given in this session.
+                self.weights.step(
+                    &record.question,
+                    &z.history,
+                    elapsed_days,
+                    record.score,
+                    &self.config,
+                );
+            }
+            z.history.update(record.score);
+            z.last_time = Some(record.time);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            let z = self.questions.iter().find(|v| v.question == *question)?;
+            let elapsed_days = at_time
+                .duration_since(z.last_time?)
+                .unwrap_or_default()
+                .as_secs_f64()
+                / SECONDS_PER_DAY;
+            Some(
+                self.weights
+                    .predict_recall(&z.question, &z.history, elapsed_days, &self.config),
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_train_transform_difficulty() {
+            // Transform 0 is always recalled after a day, transform 1 is always forgotten.
+            let start = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1000);
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let mut records = vec![];
+            for l in 0..5 {
+                for t in 0..2 {
+                    let question = Question {
+                        learnable: LearnableId(l),
+                        transform: TransformId(t),
+                        ..Default::default()
+                    };
+                    for i in 0..5 {
+                        records.push(Record {
+                            question,
+                            score: if t == 0 { 1.0 } else { 0.0 },
+                            time: start + day * i,
+                        });
+                    }
+                }
+            }
+
+            let config = HalfLifeRegressionConfig::default();
+            let mut weights = HalfLifeRegressionWeights::default();
+            weights.train(&records, &config);
+            assert!(weights.transform[&TransformId(0)] > weights.transform[&TransformId(1)]);
+        }
+    }
+}
+
+// Bayesian recall estimation, following Ebisu.
+// https://fasiha.github.io/ebisu/
+pub mod ebisu {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+
+    /*
+        Summarized from the Ebisu documentation;
+
+        The recall probability p_t at a time t after the last review is modelled with a Beta
+        prior, p_t ~ Beta(alpha, beta). Under exponential forgetting the recall at any other time
+        t_now follows from p_now = p_t ^ (t_now / t), such that with delta = t_now / t:
+            E[p_now] = B(alpha + delta, beta) / B(alpha, beta)
+
+        A quiz at t_now is treated as a noisy binary observation z; with q1 = P(z | remembered) and
+        q0 = P(z | forgotten). A score s gives z = s > 0.5, q1 = max(s, 1 - s) and q0 = 1 - q1, so
+        a score of 1.0 or 0.0 is a certain observation and 0.5 carries no information.
+
+        The posterior is not a Beta distribution, so its first two moments are computed and a Beta
+        distribution is fitted to these at the time where its expected recall is 0.5, the new
+        half-life.
+    */
+
+    const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+    /// Natural logarithm of the gamma function, Lanczos approximation.
+    fn ln_gamma(x: f64) -> f64 {
+        const G: f64 = 7.0;
+        const COEFFICIENTS: [f64; 9] = [
+            0.999_999_999_999_809_9,
+            676.520_368_121_885_1,
+            -1_259.139_216_722_402_8,
+            771.323_428_777_653_1,
+            -176.615_029_162_140_6,
+            12.507_343_278_686_905,
+            -0.138_571_095_265_720_12,
+            9.984_369_578_019_572e-6,
+            1.505_632_735_149_311_6e-7,
+        ];
+        if x < 0.5 {
+            // Reflection formula.
+            let pi = std::f64::consts::PI;
+            return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+        }
+        let x = x - 1.0;
+        let mut a = COEFFICIENTS[0];
+        let t = x + G + 0.5;
+        for (i, c) in COEFFICIENTS.iter().enumerate().skip(1) {
+            a += c / (x + i as f64);
+        }
+        0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+    }
+
+    /// Natural logarithm of the beta function.
+    fn ln_beta(a: f64, b: f64) -> f64 {
+        ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+    }
+
+    /// Beta distribution over the probability of recall at time half_life after the review.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    pub struct RecallModel {
+        pub alpha: f64,
+        pub beta: f64,
+        /// Time in days at which the recall distribution applies.
+        pub half_life: f64,
+    }
+
+    impl RecallModel {
+        /// Expected probability of recall elapsed_days after the last review.
+        pub fn predict_recall(&self, elapsed_days: f64) -> f64 {
+            let delta = elapsed_days / self.half_life;
+            (ln_beta(self.alpha + delta, self.beta) - ln_beta(self.alpha, self.beta)).exp()
+        }
+
+        /// The time in days at which the expected recall is 0.5.
+        pub fn expected_half_life(&self) -> f64 {
+            // Expected recall decreases monotonically with time, bisect in log space.
+            let (mut low, mut high) = (-10.0f64, 10.0f64);
+            for _ in 0..64 {
+                let mid = 0.5 * (low + high);
+                if self.predict_recall(self.half_life * mid.exp()) > 0.5 {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            self.half_life * (0.5 * (low + high)).exp()
+        }
+
+        /// Log of the moment E[p_t^m] of the posterior after a quiz at delta, up to the
+        /// normalisation that is shared between all moments.
+        fn ln_unnormalized_moment(&self, m: f64, delta: f64, z: bool, q0: f64, q1: f64) -> f64 {
+            let a = ln_beta(self.alpha + m, self.beta);
+            let b = ln_beta(self.alpha + delta + m, self.beta);
+            if z {
+                // Likelihood q0 + (q1 - q0) * p^delta.
+                if q0 > 0.0 {
+                    let (x, y) = (q0.ln() + a, (q1 - q0).ln() + b);
+                    x.max(y) + (1.0 + (x.min(y) - x.max(y)).exp()).ln()
+                } else {
+                    q1.ln() + b
+                }
+            } else {
+                // Likelihood (1 - q0) - (q1 - q0) * p^delta.
+                let (x, y) = ((1.0 - q0).ln() + a, (q1 - q0).ln() + b);
+                x + (1.0 - (y - x).exp()).max(f64::MIN_POSITIVE).ln()
+            }
+        }
+
+        /// Posterior model after a quiz with the provided score, elapsed_days after the review.
+        pub fn update(&self, score: Score, elapsed_days: f64) -> RecallModel {
+            if elapsed_days <= 0.0 {
+                // No time passed, the quiz carries no information about forgetting.
+                return *self;
+            }
+            let z = score > 0.5;
+            let q1 = score.max(1.0 - score);
+            let q0 = 1.0 - q1;
+            if q1 - q0 <= 0.0 {
+                return *self;
+            }
+            let delta = elapsed_days / self.half_life;
+
+            // Fit a beta distribution to the posterior moments at time t_back.
+            let fit = |t_back: f64| {
+                let d = t_back / self.half_life;
+                let ln_norm = self.ln_unnormalized_moment(0.0, delta, z, q0, q1);
+                let mean = (self.ln_unnormalized_moment(d, delta, z, q0, q1) - ln_norm).exp();
+                let second =
+                    (self.ln_unnormalized_moment(2.0 * d, delta, z, q0, q1) - ln_norm).exp();
+                let var = (second - mean * mean).max(1e-12);
+                let mean = mean.clamp(1e-6, 1.0 - 1e-6);
+                let common = (mean * (1.0 - mean) / var - 1.0).max(1e-3);
+                RecallModel {
+                    alpha: mean * common,
+                    beta: (1.0 - mean) * common,
+                    half_life: t_back,
+                }
+            };
+
+            // Fit at the old half-life first, then refit at the half-life of that posterior such
+            // that the model stays balanced around a recall of 0.5.
+            let intermediate = fit(self.half_life);
+            fit(intermediate.expected_half_life())
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct EbisuConfig {
+        /// Model for a question that was never answered.
+        pub initial: RecallModel,
+        /// Questions with an expected recall below this are due.
+        pub recall_threshold: f64,
+    }
+
+    impl Default for EbisuConfig {
+        fn default() -> EbisuConfig {
+            EbisuConfig {
+                initial: RecallModel {
+                    alpha: 3.0,
+                    beta: 3.0,
+                    half_life: 1.0,
+                },
+                recall_threshold: 0.5,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct QuestionInfo {
+        /// The question itself.
+        question: Question,
+
+        /// Last time this question was asked, None if it was never answered.
+        last_time: Option<std::time::SystemTime>,
+
+        /// The recall model for this question.
+        model: RecallModel,
+    }
+
+    impl QuestionInfo {
+        fn elapsed_days(&self, at_time: std::time::SystemTime) -> Option<f64> {
+            self.last_time.map(|last_time| {
+                at_time
+                    .duration_since(last_time)
+                    .unwrap_or_default()
+                    .as_secs_f64()
+                    / SECONDS_PER_DAY
+            })
+        }
+
+        fn update(&mut self, record: &Record) {
+            if let Some(elapsed_days) = self.elapsed_days(record.time) {
+                self.model = self.model.update(record.score, elapsed_days);
+            }
+            self.last_time = Some(record.time);
+        }
+    }
+
+    /// A selector that asks the question with the lowest expected recall, if that is below the
+    /// threshold.
+    #[derive(Debug)]
+    pub struct EbisuSelector {
+        questions: Vec<QuestionInfo>,
+        clock: std::sync::Arc<dyn Clock>,
+        config: EbisuConfig,
+    }
+    impl EbisuSelector {
+        pub fn new(config: EbisuConfig) -> Self {
+            EbisuSelector {
+                questions: vec![],
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                config,
+            }
+        }
+    }
+
+    impl Selector for EbisuSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock;
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.questions.clear();
+            for question in questions.iter() {
+                let records = recorder
+                    .get_records_by_question(question)
+                    .expect("Should return empty if unknown");
+
+                let mut info = QuestionInfo {
+                    question: *question,
+                    last_time: None,
+                    model: self.config.initial,
+                };
+                for record in records.iter() {
+                    info.update(record);
+                }
+                self.questions.push(info);
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve the question with the lowest expected recall, questions that were never
+        /// answered come first. Returns None if all expected recalls exceed the threshold.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            let now = self.clock.now();
+            self.questions
+                .iter()
+                .filter(|z| allowed(&z.question))
+                .map(|z| {
+                    let recall = z
+                        .elapsed_days(now)
+                        .map(|d| z.model.predict_recall(d))
+                        .unwrap_or(0.0);
+                    (z.question, recall)
+                })
+                .filter(|(_, recall)| *recall < self.config.recall_threshold)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(question, _)| question)
+        }
+
+        /// Store answer to a question.
+        fn store_record(&mut self, record: &Record) {
+            let z = self
+                .questions
+                .iter_mut()
+                .find(|v| v.question == record.question)
+                .expect("Passed question for which we don't have a record.");
+            z.update(record);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            let z = self.questions.iter().find(|v| v.question == *question)?;
+            z.elapsed_days(at_time).map(|d| z.model.predict_recall(d))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_update() {
+            assert!((ln_gamma(5.0) - 24.0f64.ln()).abs() < 1e-10);
+
+            let model = EbisuConfig::default().initial;
+            assert!((model.predict_recall(model.half_life) - 0.5).abs() < 1e-9);
+            assert!((model.expected_half_life() - model.half_life).abs() < 1e-6);
+
+            // A success grows the half-life, a failure shrinks it; a score of 0.5 says nothing.
+            let success = model.update(1.0, 1.0);
+            let failure = model.update(0.0, 1.0);
+            assert!(success.half_life > model.half_life);
+            assert!(failure.half_life < model.half_life);
+            assert_eq!(model.update(0.5, 1.0), model);
+
+            // Partial success lies in between.
+            let hesitated = model.update(0.8, 1.0);
+            assert!(hesitated.half_life > model.half_life);
+            assert!(hesitated.half_life < success.half_life);
+
+            // The updated model stays balanced around its half-life.
+            assert!((success.predict_recall(success.half_life) - 0.5).abs() < 1e-3);
+        }
+    }
+}
+
+pub mod dummy {
+    use super::*;
+    /// Trivial selector that yields entries in order.
+    #[derive(Debug, Default)]
+    pub struct DummySelector {
+        edges: Vec<(Question, Vec<Score>)>,
+    }
+    impl DummySelector {
+        pub fn new() -> Self {
+            DummySelector { edges: vec![] }
+        }
+    }
+
+    impl Selector for DummySelector {
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.edges.clear();
+            for q in questions.iter() {
+                let records = recorder
+                    .get_records_by_question(q)
+                    .expect("Should return empty if unknown");
+                let scores = records.iter().map(|x| x.score).collect::<_>();
+                self.edges.push((*q, scores));
+            }
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve a question to ask.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            // Just return things in order, skipping the ones that are not allowed.
+            for _ in 0..self.edges.len() {
+                let first = self.edges.remove(0);
+                self.edges.push(first.clone());
+                if allowed(&first.0) {
+                    return Some(first.0);
+                }
+            }
+            None
+        }
+
+        /// Store answer to a question.
+        fn store_record(&mut self, _record: &Record) {}
+    }
+}
+
+// Enhancing human learning via spaced repetition optimization
+// https://www.pnas.org/doi/full/10.1073/pnas.1815156116
+// https://github.com/Networks-Learning/memorize/
+pub mod memorize {
+    /*
+        Summarized from the paper;
+
+        Exponential forgetting curve model of memory
+        Recall m(t) of item after review is 1.0, m(t) decays with forgetting rate n(t)
+
+            m(t) = exp( -n(t) * (t - t_last_review) )
+
+        Estimated forgetting rate changes based on correct or failed recall.
+            Correct recall:
+                n(t + dt) = (1 - alpha) * n(t)
+            Failed recall:
+                n(t + dt) = (1 + beta) * n(t)
+
+        The reviewing intensity is then given by:
+            u(t) = q^(-0.5) * (1 - m(t))
+
+        Perfect recall m(t) = 1 results in rate of review of 0.
+        Forgotten recall m(t) = 0 results in review events at rate q^(-0.5) per unit time.
+
+        In the paper, this is then transformed from rate-of-reviewing to next-review-time with a
+        thinning algorithm.
+    */
+
+    /// n_t is the forgetting rate, t is the current time, t_last_review is previous test.
+    pub fn recall(n_t: f64, t: f64, t_last_review: f64) -> f64 {
+        assert!(t_last_review <= t, "t should be greater than t_last_review");
+        ((-n_t) * (t - t_last_review)).exp()
+    }
+
+    /// Review intensity based on the recall curve and review rate.
+    pub fn review_intensity(q: f64, recall: f64) -> f64 {
+        q.powf(-0.5) * (1.0 - recall)
+    }
+
+    /// Time transform.
+    pub fn intensity(n_t: f64, t: f64, q: f64) -> f64 {
+        (1.0 / q.sqrt()) * (1.0 - (-n_t * t).exp())
+    }
+
+    /// Calculate the next time to review based on the intensity, by sampling from the review
+    /// intensity with a thinning algorithm. The time is relative to the last review, it returns
+    /// None if the review would be beyond t_max. The units of time for t_max, n_t and q must
+    /// match; with n_t in per second this results in very small intensities, so the scheduler
+    /// uses days.
+    pub fn next_review_time<R: rand::Rng + ?Sized>(
+        n_t: f64,
+        q: f64,
+        t_max: f64,
+        rng: &mut R,
+    ) -> Option<f64> {
+        use rand_distr::Distribution;
+        // The intensity is bounded by q^(-0.5), propose events at that rate and accept them with
+        // probability intensity / max_intensity.
+        let max_intensity = 1.0 / q.sqrt();
+        let exp = rand_distr::Exp::new(max_intensity).unwrap();
+        let mut t = 0.0;
+        loop {
+            let t_ = exp.sample(rng);
+            if t_ + t > t_max {
+                return None; // Beyond max scheduling interval.
+            }
+            t += t_;
+            let proposed_intensity = intensity(n_t, t, q);
+            if rng.gen::<f64>() < (proposed_intensity / max_intensity) {
+                return Some(t);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_examples() {
+            // We want time in seconds... but that makes n(t) really small. Which sort of makes sense
+            // as 1 day is 86400 ~ 100e3 s, which puts 5-e6 (reasonable decay for seconds) at 0.5 per
+            // day.
+            // First day recall: 0.64
+            // Second day recall: 0.42
+            // Third day recall: 0.27
+            for t in 0..10 {
+                let t = (t as f64) * (3600.0 * 24.0);
+                let n_t = 5e-6;
+                let recallt = recall(n_t, t, 0.0);
+                let q = 1.0;
+                let reviewt = review_intensity(q, recallt);
+                println!("Recall {t: >10}: {recallt}");
+                println!("           review: {reviewt}");
+            }
+        }
+
+        #[test]
+        fn test_next_review_time() {
+            use rand::SeedableRng;
+            let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+            // Work in days, with the same per day forgetting rate as above.
+            let t_max = 30.0;
+            let q = 1.0;
+            let mean_review = |n_t: f64, rng: &mut rand::rngs::StdRng| {
+                let samples = (0..1000)
+                    .map(|_| next_review_time(n_t, q, t_max, rng).unwrap_or(t_max))
+                    .collect::<Vec<_>>();
+                assert!(samples.iter().all(|t| *t > 0.0 && *t <= t_max));
+                samples.iter().sum::<f64>() / samples.len() as f64
+            };
+            let easy = mean_review(0.05, &mut rng);
+            let hard = mean_review(5e-6 * 86400.0, &mut rng);
+            // Questions that are forgotten quicker are reviewed sooner.
+            assert!(hard < easy);
+            assert!(hard > 1.0 && hard < 5.0);
+            assert!(easy > 4.0 && easy < 10.0);
+        }
+    }
+
+    /// A selector based on the recall curve of this paper.
+    pub mod recall_curve {
+        use super::{recall, review_intensity};
+        use crate::traits::*;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Clone, Deserialize, Serialize)]
+        pub struct RecallCurveConfig {
+            /// Fraction by which the forgetting rate decreases on a correct answer.
+            pub n_t_alpha_correct: f64,
+            /// Fraction by which the forgetting rate increases on an incorrect answer.
+            pub n_t_beta_incorrect: f64,
+            /// The forgetting rate (per second) of a question that was never answered.
+            pub n_t_default: f64,
+            /// Review rate, higher values result in fewer reviews.
+            pub q: f64,
+            /// Score for which the forgetting rate stays the same. Higher scores decrease it, up
+            /// to the full alpha for a perfect score, lower scores increase it, up to the full
+            /// beta for a score of zero. If None only a perfect score is correct, it decreases
+            /// the forgetting rate by alpha and any other score increases it by beta.
+            #[serde(default)]
+            pub neutral_score: Option<f64>,
+            /// End the session once the predicted recall of all questions is at least this, the
+            /// session never ends if None. Questions that were never answered have to be asked.
+            #[serde(default)]
+            pub stop_recall: Option<f64>,
+        }
+
+        impl Default for RecallCurveConfig {
+            fn default() -> RecallCurveConfig {
+                RecallCurveConfig {
+                    n_t_alpha_correct: 0.05,
+                    n_t_beta_incorrect: 0.2,
+                    q: 1.0,
+                    n_t_default: 5e-6,
+                    neutral_score: None,
+                    stop_recall: None,
+                }
+            }
+        }
+
+        impl RecallCurveConfig {
+            /// Update the forgetting rate n_t based on the score obtained, the change is
+            /// proportional to the distance of the score from the neutral score if there is one.
+            pub fn update_n_t(&self, n_t: f64, score: Score) -> f64 {
+                let Some(neutral) = self.neutral_score else {
+                    return if score == 1.0 {
+                        // correct.
+                        n_t * (1.0 - self.n_t_alpha_correct)
+                    } else {
+                        // fail.
+                        n_t * (1.0 + self.n_t_beta_incorrect)
+                    };
+                };
+                let neutral = neutral.clamp(0.0, 1.0);
+                if score >= neutral {
+                    // correct, to some degree.
+                    let fraction = if neutral < 1.0 {
+                        (score - neutral) / (1.0 - neutral)
+                    } else {
+                        1.0
+                    };
+                    n_t * (1.0 - self.n_t_alpha_correct * fraction)
+                } else {
+                    // fail, to some degree.
+                    let fraction = (neutral - score) / neutral;
+                    n_t * (1.0 + self.n_t_beta_incorrect * fraction)
+                }
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        struct QuestionInfo {
+            question: Question,
+            records: Vec<Record>,
+            last_time: std::time::SystemTime,
+            n_t: f64,
+        }
+
+        #[derive(Debug)]
+        pub struct RecallCurveSelector {
+            questions: Vec<QuestionInfo>,
+            clock: std::sync::Arc<dyn Clock>,
+            rng: crate::rng::SelectorRng,
+            config: RecallCurveConfig,
+            /// Why the last question was chosen.
+            last_explanation: Option<Explanation>,
+        }
+        impl RecallCurveSelector {
+            pub fn new(config: RecallCurveConfig) -> Self {
+                RecallCurveSelector {
+                    questions: vec![],
+                    clock: std::sync::Arc::new(crate::clock::SystemClock),
+                    rng: Default::default(),
+                    config,
+                    last_explanation: None,
+                }
+            }
+        }
+
+        impl Selector for RecallCurveSelector {
+            fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+                self.clock = clock;
+            }
+
+            fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+                self.rng = rng;
+            }
+
+            fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+                self.questions.clear();
+                let now = self.clock.now();
+                for question in questions.iter() {
+                    let records = recorder
+                        .get_records_by_question(question)
+                        .expect("Should return empty if unknown");
+
+                    let mut last_time = now;
+                    let mut n_t = self.config.n_t_default;
+                    // Now, update n_t based on the past performance.
+                    for record in records.iter() {
+                        last_time = record.time;
+                        n_t = self.config.update_n_t(n_t, record.score);
+                    }
+
+                    self.questions.push(QuestionInfo {
+                        question: *question,
+                        records,
+                        last_time,
+                        n_t,
+                    });
+                }
+            }
+
+            fn get_question(&mut self) -> Option<Question> {
+                self.get_question_filtered(&|_| true)
+            }
+
+            /// Retrieve a question to ask.
+            fn get_question_filtered(
+                &mut self,
+                allowed: &dyn Fn(&Question) -> bool,
+            ) -> Option<Question> {
+                self.last_explanation = None;
+                // Here, we calculate the review intensity for each question on hand.
+                // then we pick with a weighting.
+                let allowed_indices = (0..self.questions.len())
+                    .filter(|i| allowed(&self.questions[*i].question))
+                    .collect::<Vec<_>>();
+                if allowed_indices.is_empty() {
+                    return None;
+                }
+                let now = self.clock.now();
+                if let Some(stop_recall) = self.config.stop_recall {
+                    let done = allowed_indices.iter().all(|i| {
+                        let question = &self.questions[*i].question;
+                        self.predict_recall(question, now).unwrap_or(0.0) >= stop_recall
+                    });
+                    if done {
+                        return None;
+                    }
+                }
+                use rand_distr::Distribution;
+                let weights = self
+                    .questions
+                    .iter()
+                    .map(|z| {
+                        if !allowed(&z.question) {
+                            return 0.0;
+                        }
+                        let t = now
+                            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                            .expect("can this fail?");
+                        let t_last = z
+                            .last_time
+                            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                            .expect("can this fail?");
+                        let recallt = recall(z.n_t, t.as_secs_f64(), t_last.as_secs_f64());
+                        review_intensity(self.config.q, recallt)
+                    })
+                    .collect::<Vec<f64>>();
+
+                // Create the distribution based on these weights and return the picked index. If
+                // everything was just reviewed all weights are zero, pick uniformly in that case.
+                let index = match rand::distributions::WeightedIndex::new(&weights) {
+                    Ok(dist) => dist.sample(&mut self.rng),
+                    Err(_) => {
+                        use rand::seq::SliceRandom;
+                        *allowed_indices.choose(&mut self.rng).unwrap()
+                    }
+                };
+
+                let question = self.questions[index].question;
+                let total = weights.iter().sum::<f64>();
+                self.last_explanation = Some(Explanation {
+                    question,
+                    pool: "weighted".to_owned(),
+                    overdue_seconds: None,
+                    recall: self.predict_recall(&question, now),
+                    state: vec![
+                        (
+                            "forgetting rate".to_owned(),
+                            format!("{:.3} per day", self.questions[index].n_t * 86400.0),
+                        ),
+                        (
+                            "review intensity".to_owned(),
+                            format!("{:.4}", weights[index]),
+                        ),
+                        (
+                            "probability".to_owned(),
+                            format!(
+                                "{:.1}%",
+                                100.0 * weights[index] / total.max(f64::MIN_POSITIVE)
+                            ),
+                        ),
+                    ],
+                });
+                Some(question)
+            }
+
+            /// Store answer to a question.
+            fn store_record(&mut self, record: &Record) {
+                // Update the internal record for this question.
+                let z = self
+                    .questions
+                    .iter_mut()
+                    .find(|v| v.question == record.question)
+                    .expect("Passed question for which we don't have a record.");
+                z.records.push(*record);
+                z.last_time = record.time;
+                z.n_t = self.config.update_n_t(z.n_t, record.score);
+            }
+
+            fn predict_recall(
+                &self,
+                question: &Question,
+                at_time: std::time::SystemTime,
+            ) -> Option<f64> {
+                let z = self.questions.iter().find(|v| v.question == *question)?;
+                if z.records.is_empty() {
+                    return None;
+                }
+                let t = at_time
+                    .duration_since(z.last_time)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                Some(recall(z.n_t, t, 0.0))
+            }
+
+            fn explain(&self) -> Option<Explanation> {
+                self.last_explanation.clone()
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use crate::algorithm::test_clock;
+            use crate::recorder::MemoryRecorder;
+
+            #[test]
+            fn test_graded_stop() {
+                // By default only a perfect score is correct.
+                let config = RecallCurveConfig::default();
+                let n_t = config.n_t_default;
+                assert_eq!(config.update_n_t(n_t, 1.0), n_t * 0.95);
+                assert_eq!(config.update_n_t(n_t, 0.8), n_t * 1.2);
+                assert_eq!(config.update_n_t(n_t, 0.0), n_t * 1.2);
+
+                let config = RecallCurveConfig {
+                    neutral_score: Some(0.5),
+                    stop_recall: Some(0.9),
+                    ..Default::default()
+                };
+                // A hesitant answer decreases the forgetting rate, but less than a perfect one.
+                assert!(config.update_n_t(n_t, 1.0) < config.update_n_t(n_t, 0.8));
+                assert!(config.update_n_t(n_t, 0.8) < n_t);
+                assert_eq!(config.update_n_t(n_t, 0.5), n_t);
+                assert!(config.update_n_t(n_t, 0.2) > n_t);
+
+                let day = std::time::Duration::from_secs(24 * 60 * 60);
+                let clock = test_clock();
+                let question = Question::default();
+                let mut selector = RecallCurveSelector::new(config);
+                selector.set_clock(clock.clone());
+                selector.set_questions(&[question], &MemoryRecorder::new());
+
+                // Asked until answered, after that the recall is high until time passes.
+                assert_eq!(selector.get_question(), Some(question));
+                selector.store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now(),
+                });
+                assert_eq!(selector.get_question(), None);
+                clock.advance(day);
+                assert_eq!(selector.get_question(), Some(question));
+
+                // The recall decays from the time of the answer, not from when it was stored.
+                selector.store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now() - day,
+                });
+                assert!(selector.predict_recall(&question, clock.now()).unwrap() < 1.0);
+            }
+        }
+    }
+
+    /// A selector that schedules a concrete next review time for each question, sampled from the
+    /// review intensity of the paper.
+    pub mod scheduler {
+        use super::next_review_time;
+        use super::recall_curve::RecallCurveConfig;
+        use crate::traits::*;
+        use serde::{Deserialize, Serialize};
+
+        const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+        #[derive(Debug, Clone, Deserialize, Serialize)]
+        pub struct MemorizeConfig {
+            /// The forgetting rate model, n_t in this configuration is per second and its q is
+            /// not used.
+            pub recall_curve: RecallCurveConfig,
+            /// Review rate parameter with time in days, higher values result in fewer reviews.
+            pub q: f64,
+            /// Maximum time between reviews in days.
+            pub t_max: f64,
+        }
+
+        impl Default for MemorizeConfig {
+            fn default() -> MemorizeConfig {
+                MemorizeConfig {
+                    recall_curve: Default::default(),
+                    q: 1.0,
+                    t_max: 365.0,
+                }
+            }
+        }
+
+        /// Seed for the review time sampling, derived from the question and the time of the
+        /// review. This makes the schedule identical when the records are replayed.
+        fn review_seed(question: &Question, time: std::time::SystemTime) -> u64 {
+            // splitmix64, to mix the values into a well distributed seed.
+            let mix = |mut z: u64| {
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+                z ^ (z >> 31)
+            };
+            let time = time
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            [
+                question.learnable.0,
+                question.from.0,
+                question.transform.0,
+                question.to.0,
+                time,
+            ]
+            .iter()
+            .fold(0x9e3779b97f4a7c15, |acc, v| {
+                mix(acc.wrapping_add(*v).wrapping_add(0x9e3779b97f4a7c15))
+            })
+        }
+
+        #[derive(Debug, Clone)]
+        struct QuestionInfo {
+            /// The question itself.
+            question: Question,
+
+            /// Forgetting rate, per second.
+            n_t: f64,
+
+            /// Time at which this question is due, None if it was never answered.
+            next_review: Option<std::time::SystemTime>,
+        }
+
+        impl QuestionInfo {
+            fn update(&mut self, config: &MemorizeConfig, record: &Record) {
+                use rand::SeedableRng;
+                self.n_t = config.recall_curve.update_n_t(self.n_t, record.score);
+                let mut rng =
+                    rand::rngs::StdRng::seed_from_u64(review_seed(&self.question, record.time));
+                let n_t_days = self.n_t * SECONDS_PER_DAY;
+                let days = next_review_time(n_t_days, config.q, config.t_max, &mut rng)
+                    .unwrap_or(config.t_max);
+                self.next_review =
+                    Some(record.time + std::time::Duration::from_secs_f64(days * SECONDS_PER_DAY));
+            }
+        }
+
+        /// Selector that asks questions once their sampled review time has passed.
+        #[derive(Debug)]
+        pub struct MemorizeSelector {
+            questions: Vec<QuestionInfo>,
+            clock: std::sync::Arc<dyn Clock>,
+            config: MemorizeConfig,
+            last_explanation: Option<Explanation>,
+        }
+        impl MemorizeSelector {
+            pub fn new(config: MemorizeConfig) -> Self {
+                MemorizeSelector {
+                    questions: vec![],
+                    clock: std::sync::Arc::new(crate::clock::SystemClock),
+                    config,
+                    last_explanation: None,
+                }
+            }
+
+            fn explanation(&self, z: &QuestionInfo) -> Explanation {
+                let now = self.clock.now();
+                Explanation {
+                    question: z.question,
+                    pool: if z.next_review.is_some() {
+                        "due"
+                    } else {
+                        "new"
+                    }
+                    .to_owned(),
+                    overdue_seconds: z
+                        .next_review
+                        .map(|t| crate::algorithm::seconds_between(t, now)),
+                    recall: None,
+                    state: vec![(
+                        "forgetting rate".to_owned(),
+                        format!("{:.3} per day", z.n_t * SECONDS_PER_DAY),
+                    )],
+                }
+            }
+        }
+
+        impl Selector for MemorizeSelector {
+            fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+                self.clock = clock;
+            }
+
+            fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+                self.questions.clear();
+                for question in questions.iter() {
+                    let records = recorder
+                        .get_records_by_question(question)
+                        .expect("Should return empty if unknown");
+
+                    let mut info = QuestionInfo {
+                        question: *question,
+                        n_t: self.config.recall_curve.n_t_default,
+                        next_review: None,
+                    };
+                    for record in records.iter() {
+                        info.update(&self.config, record);
+                    }
+                    self.questions.push(info);
+                }
+            }
+
+            fn get_question(&mut self) -> Option<Question> {
+                self.get_question_filtered(&|_| true)
+            }
+
+            /// Retrieve the question that is most overdue, questions that were never answered
+            /// come first. Returns None if no question is due.
+            fn get_question_filtered(
+                &mut self,
+                allowed: &dyn Fn(&Question) -> bool,
+            ) -> Option<Question> {
+                let now = self.clock.now();
+                let z = self
+                    .questions
+                    .iter()
+                    .filter(|z| z.next_review.map(|t| t <= now).unwrap_or(true))
+                    .filter(|z| allowed(&z.question))
+                    .min_by_key(|z| z.next_review);
+                self.last_explanation = z.map(|z| self.explanation(z));
+                z.map(|z| z.question)
+            }
+
+            /// Store answer to a question.
+            fn store_record(&mut self, record: &Record) {
+                let z = self
+                    .questions
+                    .iter_mut()
+                    .find(|v| v.question == record.question)
+                    .expect("Passed question for which we don't have a record.");
+                z.update(&self.config, record);
+            }
+
+            fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+                let now = self.clock.now();
+                let due_times = self.questions.iter().map(|z| z.next_review.unwrap_or(now));
+                Some(crate::algorithm::due_per_day(now, due_times, days))
+            }
+
+            fn explain(&self) -> Option<Explanation> {
+                self.last_explanation.clone()
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use crate::algorithm::test_clock;
+            use crate::recorder::MemoryRecorder;
+
+            #[test]
+            fn test_explain() {
+                let clock = test_clock();
+                let question = Question::default();
+                let mut selector = MemorizeSelector::new(Default::default());
+                selector.set_clock(clock.clone());
+                selector.set_questions(&[question], &MemoryRecorder::new());
+
+                assert_eq!(selector.get_question(), Some(question));
+                let explanation = selector.explain().unwrap();
+                assert_eq!(explanation.pool, "new");
+                assert_eq!(explanation.overdue_seconds, None);
+
+                // Once answered it is explained by how overdue its sampled review time is.
+                selector.store_record(&Record {
+                    question,
+                    score: 1.0,
+                    time: clock.now(),
+                });
+                assert_eq!(selector.get_question(), None);
+                assert_eq!(selector.explain(), None);
+                clock.advance(std::time::Duration::from_secs_f64(
+                    selector.config.t_max * SECONDS_PER_DAY + 1.0,
+                ));
+                assert_eq!(selector.get_question(), Some(question));
+                let explanation = selector.explain().unwrap();
+                assert_eq!(explanation.pool, "due");
+                assert!(explanation.overdue_seconds.unwrap() > 0.0);
+            }
+        }
+    }
+}
+
+// Burying of siblings, the other questions of the same learnable.
+pub mod sibling_bury {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct SiblingBuryConfig {
+        /// Hours after answering a question before other questions of the same learnable may be
+        /// asked. The default of 18 hours defers them to roughly the next day.
+        pub gap_hours: f64,
+        /// Instead of the gap, hold back the other questions until the next day starts, according
+        /// to the day boundary.
+        #[serde(default)]
+        pub until_next_day: bool,
+    }
+
+    impl Default for SiblingBuryConfig {
+        fn default() -> SiblingBuryConfig {
+            SiblingBuryConfig {
+                gap_hours: 18.0,
+                until_next_day: false,
+            }
+        }
+    }
+
+    /// Wraps another selector and holds back the siblings of answered questions, such that asking
+    /// one direction of a card doesn't give away the answer to the other direction.
+    #[derive(Debug)]
+    pub struct SiblingBurySelector {
+        selector: Box<dyn Selector>,
+        clock: std::sync::Arc<dyn Clock>,
+        config: SiblingBuryConfig,
+        day_boundary: crate::clock::DayBoundary,
+        /// Questions that were answered, with the time of the last answer, per learnable.
+        answered: HashMap<LearnableId, Vec<(Question, std::time::SystemTime)>>,
+    }
+
+    impl SiblingBurySelector {
+        pub fn new(selector: Box<dyn Selector>, config: SiblingBuryConfig) -> Self {
+            SiblingBurySelector {
+                selector,
+                clock: std::sync::Arc::new(crate::clock::SystemClock),
+                config,
+                day_boundary: Default::default(),
+                answered: Default::default(),
+            }
+        }
+
+        fn add_answer(&mut self, record: &Record) {
+            let answers = self.answered.entry(record.question.learnable).or_default();
+            if let Some(entry) = answers.iter_mut().find(|(q, _)| *q == record.question) {
+                entry.1 = entry.1.max(record.time);
+            } else {
+                answers.push((record.question, record.time));
+            }
+        }
+
+        /// Collect the answers from the records.
+        fn set_answered(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.answered.clear();
+            let records = crate::algorithm::history(recorder, questions);
+            for record in records.iter() {
+                self.add_answer(record);
+            }
+        }
+
+        /// Whether a sibling of this question was answered within the gap, or today.
+        fn is_buried(
+            answered: &HashMap<LearnableId, Vec<(Question, std::time::SystemTime)>>,
+            config: &SiblingBuryConfig,
+            boundary: &crate::clock::DayBoundary,
+            now: std::time::SystemTime,
+            question: &Question,
+        ) -> bool {
+            let gap = std::time::Duration::from_secs_f64(config.gap_hours * 60.0 * 60.0);
+            let buried_until = |time: std::time::SystemTime| {
+                if config.until_next_day {
+                    boundary.days_after(time, 1)
+                } else {
+                    time + gap
+                }
+            };
+            answered
+                .get(&question.learnable)
+                .map(|answers| {
+                    answers
+                        .iter()
+                        .any(|(q, time)| q != question && now < buried_until(*time))
+                })
+                .unwrap_or(false)
+        }
+    }
+
+    impl Selector for SiblingBurySelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.clock = clock.clone();
+            self.selector.set_clock(clock);
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.selector.set_rng(rng);
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.day_boundary = boundary;
+            self.selector.set_day_boundary(boundary);
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            self.set_answered(questions, recorder);
+            self.selector
+                .set_questions_with_credit(questions, recorder, credit);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve a question from the wrapped selector, leaving out the siblings of questions
+        /// answered within the gap, or today.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            let now = self.clock.now();
+            let (answered, config, boundary) = (&self.answered, &self.config, &self.day_boundary);
+            self.selector.get_question_filtered(&|q| {
+                allowed(q) && !Self::is_buried(answered, config, boundary, now, q)
+            })
+        }
+
+        fn store_record(&mut self, record: &Record) {
+            self.add_answer(record);
+            self.selector.store_record(record);
+        }
+
+        fn store_partial_credit(&mut self, record: &Record) {
+            self.selector.store_partial_credit(record);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            self.selector.predict_recall(question, at_time)
+        }
+
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            self.selector.forecast(days)
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            self.selector.progress()
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.selector.explain()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            let config = (&self.config, &self.day_boundary);
+            super::snapshot_tagged("SiblingBury", &config, &self.selector.snapshot()?)
+        }
+
+        /// Restore the wrapped selector, the answers are collected from the records as these
+        /// don't need to be replayed.
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let config = (&self.config, &self.day_boundary);
+            let state: serde_yaml::Value = super::restore_tagged("SiblingBury", &config, state)?;
+            self.selector.restore(questions, recorder, &state)?;
+            self.set_answered(questions, recorder);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::super_memo_2::SuperMemo2Selector;
+        use crate::algorithm::test_clock;
+        use crate::clock::ManualClock;
+        use crate::recorder::MemoryRecorder;
+        use std::sync::Arc;
+
+        #[test]
+        fn test_bury() {
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let clock = test_clock();
+            // Two directions of one learnable, and a separate learnable.
+            let forward = Question {
+                from: RepresentationId(1),
+                to: RepresentationId(2),
+                ..Default::default()
+            };
+            let backward = Question {
+                from: RepresentationId(2),
+                to: RepresentationId(1),
+                ..Default::default()
+            };
+            let other = Question {
+                learnable: LearnableId(1),
+                ..Default::default()
+            };
+
+            let mut selector = SiblingBurySelector::new(
+                Box::new(SuperMemo2Selector::default()),
+                Default::default(),
+            );
+            selector.set_clock(clock.clone());
+            selector.set_rng(crate::rng::SelectorRng::from_seed(1));
+            selector.set_questions(&[forward, backward, other], &MemoryRecorder::new());
+
+            // Each day only one direction is asked, together with the other learnable.
+            for _ in 0..3 {
+                let mut asked = vec![];
+                while let Some(question) = selector.get_question() {
+                    asked.push(question);
+                    selector.store_record(&Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
+                    });
+                }
+                assert!(asked.contains(&other));
+                assert_eq!(
+                    asked
+                        .iter()
+                        .filter(|q| **q == forward || **q == backward)
+                        .count(),
+                    1
+                );
+                clock.advance(day * 7);
+            }
+        }
+
+        /// Selector as implemented outside this crate, with only the required methods.
+        #[derive(Debug)]
+        struct InOrder(Vec<Question>);
+
+        impl Selector for InOrder {
+            fn set_questions(&mut self, questions: &[Question], _recorder: &dyn Recorder) {
+                self.0 = questions.to_vec();
+            }
+
+            fn get_question(&mut self) -> Option<Question> {
+                self.0.first().copied()
+            }
+
+            fn store_record(&mut self, record: &Record) {
+                self.0.retain(|q| *q != record.question);
+            }
+        }
+
+        #[test]
+        fn test_bury_external() {
+            let clock = Arc::new(ManualClock::new(std::time::SystemTime::UNIX_EPOCH));
+            let forward = Question {
+                from: RepresentationId(1),
+                to: RepresentationId(2),
+                ..Default::default()
+            };
+            let backward = Question {
+                from: RepresentationId(2),
+                to: RepresentationId(1),
+                ..Default::default()
+            };
+            let mut selector =
+                SiblingBurySelector::new(Box::new(InOrder(vec![])), Default::default());
+            selector.set_clock(clock.clone());
+            selector.set_questions(&[forward, backward], &MemoryRecorder::new());
+            assert_eq!(selector.get_question(), Some(forward));
+            selector.store_record(&Record {
+                question: forward,
+                score: 1.0,
+                time: clock.now(),
+            });
+
+            // The other direction is held back until the gap passed.
+            assert_eq!(selector.get_question(), None);
+            clock.advance(std::time::Duration::from_secs(19 * 60 * 60));
+            assert_eq!(selector.get_question(), Some(backward));
+        }
+
+        #[test]
+        fn test_bury_until_next_day() {
+            let hour = std::time::Duration::from_secs(60 * 60);
+            // Late in the evening, the next day starts at 04:00.
+            let clock = test_clock();
+            clock.advance(hour * 22);
+            let forward = Question {
+                from: RepresentationId(1),
+                to: RepresentationId(2),
+                ..Default::default()
+            };
+            let backward = Question {
+                from: RepresentationId(2),
+                to: RepresentationId(1),
+                ..Default::default()
+            };
+            let mut selector = SiblingBurySelector::new(
+                Box::new(InOrder(vec![])),
+                SiblingBuryConfig {
+                    until_next_day: true,
+                    ..Default::default()
+                },
+            );
+            selector.set_clock(clock.clone());
+            selector.set_day_boundary(crate::clock::DayBoundary::local(
+                chrono_tz::UTC,
+                "04:00".parse().unwrap(),
+            ));
+            selector.set_questions(&[forward, backward], &MemoryRecorder::new());
+            selector.store_record(&Record {
+                question: forward,
+                score: 1.0,
+                time: clock.now(),
+            });
+
+            // Held back until 04:00, not for the whole gap.
+            clock.advance(hour * 5);
+            assert_eq!(selector.get_question(), None);
+            clock.advance(hour);
+            assert_eq!(selector.get_question(), Some(backward));
+        }
+    }
+}
+
+// Transfer of knowledge between related questions.
+pub mod transfer {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct TransferConfig {
+        /// Transfer between questions of the same learnable.
+        pub share_learnable: bool,
+        /// Transfer between questions that share a representation.
+        pub share_representation: bool,
+        /// Only answers with at least this score are transferred, a failure on one question says
+        /// little about a related one.
+        pub min_score: Score,
+        /// The credit is the score of the answer multiplied by this factor.
+        pub credit: f64,
+    }
+
+    impl Default for TransferConfig {
+        fn default() -> TransferConfig {
+            TransferConfig {
+                share_learnable: true,
+                share_representation: true,
+                // Only answers after some hesitation or better are good evidence.
+                min_score: 0.8,
+                credit: 0.8,
+            }
+        }
+    }
+
+    /// Wraps another selector and passes it partial credit for the questions related to the one
+    /// answered, such that multi-sided cards converge faster, see
+    /// [`Selector::store_partial_credit`]. The credit is derived from the history again when the
+    /// questions are set, it is never stored in the recorder.
+    #[derive(Debug)]
+    pub struct TransferSelector {
+        selector: Box<dyn Selector>,
+        config: TransferConfig,
+        by_learnable: HashMap<LearnableId, Vec<Question>>,
+        by_representation: HashMap<RepresentationId, Vec<Question>>,
+    }
+
+    impl TransferSelector {
+        pub fn new(selector: Box<dyn Selector>, config: TransferConfig) -> Self {
+            TransferSelector {
+                selector,
+                config,
+                by_learnable: Default::default(),
+                by_representation: Default::default(),
+            }
+        }
+
+        /// Collect which questions are related, by learnable and by representation.
+        fn set_related(&mut self, questions: &[Question]) {
+            self.by_learnable.clear();
+            self.by_representation.clear();
+            for question in questions.iter() {
+                self.by_learnable
+                    .entry(question.learnable)
+                    .or_default()
+                    .push(*question);
+                for id in [question.from, question.to] {
+                    self.by_representation
+                        .entry(id)
+                        .or_default()
+                        .push(*question);
+                }
+            }
+        }
+
+        /// The partial credit that follows from the provided record.
+        fn transferred(&self, record: &Record) -> Vec<Record> {
+            if record.score < self.config.min_score {
+                return vec![];
+            }
+            let mut related: Vec<Question> = vec![];
+            if self.config.share_learnable {
+                if let Some(questions) = self.by_learnable.get(&record.question.learnable) {
+                    related.extend(questions);
+                }
+            }
+            if self.config.share_representation {
+                for id in [record.question.from, record.question.to] {
+                    if let Some(questions) = self.by_representation.get(&id) {
+                        related.extend(questions);
+                    }
+                }
+            }
+            let mut res: Vec<Record> = vec![];
+            for question in related {
+                if question != record.question && !res.iter().any(|r| r.question == question) {
+                    res.push(Record {
+                        question,
+                        score: (record.score * self.config.credit).clamp(0.0, 1.0),
+                        time: record.time,
+                    });
+                }
+            }
+            res
+        }
+    }
+
+    impl Selector for TransferSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.selector.set_clock(clock);
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.selector.set_rng(rng);
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.selector.set_day_boundary(boundary);
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        /// Pass all credit that follows from the history to the wrapped selector, it replays the
+        /// credit together with the answers, such that a new question that got credit before
+        /// its first answer is scheduled the same as when the answers were given.
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            self.set_related(questions);
+            let mut credits: Vec<Record> = credit.to_vec();
+            for record in crate::algorithm::history(recorder, questions).iter() {
+                credits.extend(self.transferred(record));
+            }
+            credits.sort_by_key(|r| r.time);
+            self.selector
+                .set_questions_with_credit(questions, recorder, &credits);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            self.selector.get_question_filtered(allowed)
+        }
+
+        fn store_record(&mut self, record: &Record) {
+            self.selector.store_record(record);
+            for credit in self.transferred(record) {
+                self.selector.store_partial_credit(&credit);
+            }
+        }
+
+        fn store_partial_credit(&mut self, record: &Record) {
+            self.selector.store_partial_credit(record);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            self.selector.predict_recall(question, at_time)
+        }
+
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            self.selector.forecast(days)
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            self.selector.progress()
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.selector.explain()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            super::snapshot_tagged("Transfer", &self.config, &self.selector.snapshot()?)
+        }
+
+        /// Restore the wrapped selector, its state includes the partial credit so only the
+        /// related questions are collected again.
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let state: serde_yaml::Value = super::restore_tagged("Transfer", &self.config, state)?;
+            self.selector.restore(questions, recorder, &state)?;
+            self.set_related(questions);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
         use super::*;
+        use crate::algorithm::super_memo_2::SuperMemo2Selector;
+        use crate::algorithm::test_clock;
+        use crate::recorder::MemoryRecorder;
+
+        #[test]
+        fn test_transfer() {
+            let clock = test_clock();
+            // Two directions of one learnable, and an unrelated learnable.
+            let forward = Question {
+                from: RepresentationId(1),
+                to: RepresentationId(2),
+                ..Default::default()
+            };
+            let backward = Question {
+                from: RepresentationId(2),
+                to: RepresentationId(1),
+                ..Default::default()
+            };
+            let other = Question {
+                learnable: LearnableId(1),
+                from: RepresentationId(3),
+                to: RepresentationId(4),
+                ..Default::default()
+            };
+            let questions = [forward, backward, other];
+
+            let mut selector =
+                TransferSelector::new(Box::new(SuperMemo2Selector::default()), Default::default());
+            let transferred = |selector: &TransferSelector, score: Score| {
+                selector
+                    .transferred(&Record {
+                        question: forward,
+                        score,
+                        time: clock.now(),
+                    })
+                    .iter()
+                    .map(|r| (r.question, r.score))
+                    .collect::<Vec<_>>()
+            };
+            selector.set_clock(clock.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+            assert_eq!(transferred(&selector, 1.0), vec![(backward, 0.8)]);
+            assert_eq!(transferred(&selector, 0.6), vec![]);
+
+            // Both directions are still asked, the credit only postpones the related question.
+            // Plain SuperMemo2 asks both on the same days, with transfer they spread out.
+            let days_asked = |selector: &mut dyn Selector, recorder: &mut MemoryRecorder| {
+                let start = clock.now();
+                let mut days: Vec<Vec<Question>> = vec![];
+                for day in 0..30u64 {
+                    clock.set(start + std::time::Duration::from_secs(day * 24 * 60 * 60));
+                    let mut asked = vec![];
+                    while let Some(question) = selector.get_question() {
+                        asked.push(question);
+                        let record = Record {
+                            question,
+                            score: 1.0,
+                            time: clock.now(),
+                        };
+                        selector.store_record(&record);
+                        recorder.store_record(&record).unwrap();
+                    }
+                    days.push(asked);
+                }
+                clock.set(start);
+                days
+            };
+            let together = |days: &[Vec<Question>]| {
+                days.iter()
+                    .skip(1)
+                    .filter(|d| d.contains(&forward) && d.contains(&backward))
+                    .count()
+            };
+
+            let mut plain = SuperMemo2Selector::default();
+            plain.set_clock(clock.clone());
+            plain.set_questions(&questions, &MemoryRecorder::new());
+            let plain_days = days_asked(&mut plain, &mut MemoryRecorder::new());
+
+            let mut recorder = MemoryRecorder::new();
+            let days = days_asked(&mut selector, &mut recorder);
+            assert!(days[0].contains(&forward) && days[0].contains(&backward));
+            assert!(days.iter().skip(1).any(|d| d.contains(&backward)));
+            assert!(together(&days) < together(&plain_days));
+
+            // The same state is derived from the history.
+            let mut replayed =
+                TransferSelector::new(Box::new(SuperMemo2Selector::default()), Default::default());
+            replayed.set_clock(clock.clone());
+            replayed.set_questions(&questions, &recorder);
+            assert_eq!(replayed.forecast(60), selector.forecast(60));
+
+            // Or restored from a snapshot, which includes the partial credit.
+            let mut restored =
+                TransferSelector::new(Box::new(SuperMemo2Selector::default()), Default::default());
+            restored.set_clock(clock.clone());
+            restored
+                .restore(&questions, &recorder, &selector.snapshot().unwrap())
+                .unwrap();
+            assert_eq!(restored.forecast(60), selector.forecast(60));
+            assert_eq!(transferred(&restored, 1.0), vec![(backward, 0.8)]);
+
+            // The snapshot of the plain selector has no transfer state, it is rejected.
+            assert!(restored
+                .restore(&questions, &recorder, &plain.snapshot().unwrap())
+                .is_err());
+        }
+
+        #[test]
+        fn test_transfer_converges() {
+            use crate::algorithm::fsrs::FsrsSelector;
+            let clock = test_clock();
+            let questions = [
+                Question {
+                    from: RepresentationId(1),
+                    to: RepresentationId(2),
+                    ..Default::default()
+                },
+                Question {
+                    from: RepresentationId(2),
+                    to: RepresentationId(1),
+                    ..Default::default()
+                },
+            ];
+            // Answer everything after some hesitation for two months, count the reviews needed
+            // before each question is first left alone for two weeks.
+            let reviews = |selector: &mut dyn Selector| {
+                let start = clock.now();
+                let mut recorder = MemoryRecorder::new();
+                selector.set_clock(clock.clone());
+                selector.set_questions(&questions, &recorder);
+                for day in 0..60u64 {
+                    clock.set(start + std::time::Duration::from_secs(day * 24 * 60 * 60));
+                    while let Some(question) = selector.get_question() {
+                        let record = Record {
+                            question,
+                            score: 0.8,
+                            time: clock.now(),
+                        };
+                        selector.store_record(&record);
+                        recorder.store_record(&record).unwrap();
+                    }
+                }
+                clock.set(start);
+                let records = recorder.get_records().unwrap();
+                questions
+                    .iter()
+                    .map(|question| {
+                        let times = records
+                            .iter()
+                            .filter(|r| r.question == *question)
+                            .map(|r| r.time)
+                            .collect::<Vec<_>>();
+                        times
+                            .windows(2)
+                            .position(|w| {
+                                w[1].duration_since(w[0]).unwrap()
+                                    >= std::time::Duration::from_secs(14 * 24 * 60 * 60)
+                            })
+                            .expect("Should converge within two months")
+                            + 1
+                    })
+                    .sum::<usize>()
+            };
+
+            // The question answered second got credit from the first, it needs fewer reviews.
+            let plain = reviews(&mut SuperMemo2Selector::default());
+            let transfer = reviews(&mut TransferSelector::new(
+                Box::new(SuperMemo2Selector::default()),
+                Default::default(),
+            ));
+            assert!(transfer < plain, "{transfer} < {plain}");
+
+            let plain = reviews(&mut FsrsSelector::new(Default::default()));
+            let transfer = reviews(&mut TransferSelector::new(
+                Box::new(FsrsSelector::new(Default::default())),
+                Default::default(),
+            ));
+            assert!(transfer < plain, "{transfer} < {plain}");
+        }
+    }
+}
+
+// Selectors that combine other selectors.
+pub mod combinators {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+
+    /// Matches questions by their transform and learnable.
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+    pub struct QuestionFilter {
+        /// Questions must have one of these transforms, any transform if empty.
+        #[serde(default)]
+        pub transforms: Vec<TransformId>,
+        /// Questions must be from one of these learnables, any learnable if empty.
+        #[serde(default)]
+        pub learnables: Vec<LearnableId>,
+    }
+
+    impl QuestionFilter {
+        pub fn matches(&self, question: &Question) -> bool {
+            (self.transforms.is_empty() || self.transforms.contains(&question.transform))
+                && (self.learnables.is_empty() || self.learnables.contains(&question.learnable))
+        }
+    }
+
+    /// Predicate used by the filter selector.
+    pub type QuestionPredicate = Box<dyn Fn(&Question) -> bool + Send + Sync>;
+
+    /// Only passes the questions that match the predicate to the wrapped selector.
+    pub struct FilterSelector {
+        selector: Box<dyn Selector>,
+        predicate: QuestionPredicate,
+        /// The question filter the predicate was made from, if any, for the snapshot.
+        filter: Option<QuestionFilter>,
+    }
+
+    impl FilterSelector {
+        pub fn new(selector: Box<dyn Selector>, predicate: QuestionPredicate) -> Self {
+            FilterSelector {
+                selector,
+                predicate,
+                filter: None,
+            }
+        }
+
+        /// Filter with a question filter from a configuration.
+        pub fn from_filter(selector: Box<dyn Selector>, filter: QuestionFilter) -> Self {
+            let matches = filter.clone();
+            FilterSelector {
+                filter: Some(filter),
+                ..Self::new(selector, Box::new(move |q| matches.matches(q)))
+            }
+        }
+    }
+
+    impl std::fmt::Debug for FilterSelector {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("FilterSelector")
+                .field("selector", &self.selector)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl Selector for FilterSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.selector.set_clock(clock);
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.selector.set_rng(rng);
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.selector.set_day_boundary(boundary);
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            let questions = questions
+                .iter()
+                .filter(|q| (self.predicate)(q))
+                .copied()
+                .collect::<Vec<_>>();
+            let credit = credit
+                .iter()
+                .filter(|r| (self.predicate)(&r.question))
+                .copied()
+                .collect::<Vec<_>>();
+            self.selector
+                .set_questions_with_credit(&questions, recorder, &credit);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            self.selector.get_question_filtered(allowed)
+        }
+
+        /// Store answer to a question, answers to questions that don't match are dropped as the
+        /// wrapped selector doesn't know about them.
+        fn store_record(&mut self, record: &Record) {
+            if (self.predicate)(&record.question) {
+                self.selector.store_record(record);
+            }
+        }
+
+        fn store_partial_credit(&mut self, record: &Record) {
+            if (self.predicate)(&record.question) {
+                self.selector.store_partial_credit(record);
+            }
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            if (self.predicate)(question) {
+                self.selector.predict_recall(question, at_time)
+            } else {
+                None
+            }
+        }
+
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            self.selector.forecast(days)
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            self.selector.progress()
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.selector.explain()
+        }
+
+        /// Snapshot of the wrapped selector, tagged with the question filter. A predicate can't
+        /// be compared, but the wrapped selector only restores for the same questions.
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            super::snapshot_tagged("Filter", &self.filter, &self.selector.snapshot()?)
+        }
+
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let state: serde_yaml::Value = super::restore_tagged("Filter", &self.filter, state)?;
+            let questions = questions
+                .iter()
+                .filter(|q| (self.predicate)(q))
+                .copied()
+                .collect::<Vec<_>>();
+            self.selector.restore(&questions, recorder, &state)
+        }
+    }
+
+    /// Asks the questions of the primary selector, once that has no more questions the fallback
+    /// selector is used. For example SuperMemo2 for the due reviews followed by the recall curve
+    /// selector to keep practicing the hardest questions.
+    #[derive(Debug)]
+    pub struct FallbackSelector {
+        primary: Box<dyn Selector>,
+        fallback: Box<dyn Selector>,
+        /// Whether the last question came from the fallback selector.
+        last_from_fallback: bool,
+    }
+
+    impl FallbackSelector {
+        pub fn new(primary: Box<dyn Selector>, fallback: Box<dyn Selector>) -> Self {
+            FallbackSelector {
+                primary,
+                fallback,
+                last_from_fallback: false,
+            }
+        }
+    }
+
+    impl Selector for FallbackSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.primary.set_clock(clock.clone());
+            self.fallback.set_clock(clock);
+        }
+
+        fn set_rng(&mut self, mut rng: crate::rng::SelectorRng) {
+            self.primary.set_rng(rng.split());
+            self.fallback.set_rng(rng);
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.primary.set_day_boundary(boundary);
+            self.fallback.set_day_boundary(boundary);
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            self.primary
+                .set_questions_with_credit(questions, recorder, credit);
+            self.fallback
+                .set_questions_with_credit(questions, recorder, credit);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            if let Some(question) = self.primary.get_question_filtered(allowed) {
+                self.last_from_fallback = false;
+                return Some(question);
+            }
+            self.last_from_fallback = true;
+            self.fallback.get_question_filtered(allowed)
+        }
+
+        fn store_record(&mut self, record: &Record) {
+            self.primary.store_record(record);
+            self.fallback.store_record(record);
+        }
+
+        fn store_partial_credit(&mut self, record: &Record) {
+            self.primary.store_partial_credit(record);
+            self.fallback.store_partial_credit(record);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            self.primary
+                .predict_recall(question, at_time)
+                .or_else(|| self.fallback.predict_recall(question, at_time))
+        }
+
+        /// The forecast of the primary selector, it determines the reviews that are due.
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            self.primary.forecast(days)
+        }
+
+        /// The progress of the primary selector, it determines the reviews that are due.
+        fn progress(&self) -> Option<Progress> {
+            self.primary.progress()
+        }
+
+        /// The explanation of the selector that provided the last question.
+        fn explain(&self) -> Option<Explanation> {
+            if self.last_from_fallback {
+                self.fallback.explain()
+            } else {
+                self.primary.explain()
+            }
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            snapshot_all([&self.primary, &self.fallback].into_iter())
+        }
 
-        #[test]
-        fn test_examples() {
-            // We want time in seconds... but that makes n(t) really small. Which sort of makes sense
-            // as 1 day is 86400 ~ 100e3 s, which puts 5-e6 (reasonable decay for seconds) at 0.5 per
-            // day.
-            // First day recall: 0.64
-            // Second day recall: 0.42
-            // Third day recall: 0.27
-            for t in 0..10 {
-                let t = (t as f64) * (3600.0 * 24.0);
-                let n_t = 5e-6;
-                let recallt = recall(n_t, t, 0.0);
-                let q = 1.0;
-                let reviewt = review_intensity(q, recallt);
-                println!("Recall {t: >10}: {recallt}");
-                println!("           review: {reviewt}");
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            restore_all(
+                [&mut self.primary, &mut self.fallback].into_iter(),
+                questions,
+                recorder,
+                state,
+            )
+        }
+    }
+
+    /// Interleaves several selectors, each question is taken from a selector picked at random
+    /// by weight. If the picked selector has no question, the others are tried.
+    #[derive(Debug)]
+    pub struct WeightedSelector {
+        selectors: Vec<(f64, Box<dyn Selector>)>,
+        rng: crate::rng::SelectorRng,
+        /// Index of the selector that provided the last question.
+        last: Option<usize>,
+    }
 
-                /*
-                // Even trying to correct for the days->seconds shift here, this seems pretty
-                // odd in behaviour.
-                let t_max = 10.0;
-                let n_t = n_t * 86400.0;
-                let q = q;
-                let review_next = next_review_time(n_t, q, t_max);
-                println!("                 : {review_next:?}");
-                */
+    impl WeightedSelector {
+        pub fn new(selectors: Vec<(f64, Box<dyn Selector>)>) -> Self {
+            WeightedSelector {
+                selectors,
+                rng: Default::default(),
+                last: None,
             }
         }
     }
 
-    /// A selector based on the recall curve of this paper.
-    pub mod recall_curve {
-        use super::{recall, review_intensity};
-        use crate::traits::*;
-        use serde::{Deserialize, Serialize};
+    impl Selector for WeightedSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.set_clock(clock.clone());
+            }
+        }
 
-        #[derive(Debug, Deserialize, Serialize)]
-        pub struct RecallCurveConfig {
-            n_t_alpha_correct: f64,
-            n_t_beta_incorrect: f64,
-            n_t_default: f64,
-            q: f64,
+        fn set_rng(&mut self, mut rng: crate::rng::SelectorRng) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.set_rng(rng.split());
+            }
+            self.rng = rng;
         }
 
-        impl Default for RecallCurveConfig {
-            fn default() -> RecallCurveConfig {
-                RecallCurveConfig {
-                    n_t_alpha_correct: 0.05,
-                    n_t_beta_incorrect: 0.2,
-                    q: 1.0,
-                    n_t_default: 5e-6,
-                }
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.set_day_boundary(boundary);
             }
         }
 
-        #[derive(Debug, Clone)]
-        struct QuestionInfo {
-            question: Question,
-            records: Vec<Record>,
-            last_time: std::time::SystemTime,
-            n_t: f64,
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
         }
 
-        #[derive(Debug)]
-        pub struct RecallCurveSelector {
-            questions: Vec<QuestionInfo>,
-            config: RecallCurveConfig,
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.set_questions_with_credit(questions, recorder, credit);
+            }
         }
-        impl RecallCurveSelector {
-            pub fn new(config: RecallCurveConfig) -> Self {
-                RecallCurveSelector {
-                    questions: vec![],
-                    config,
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            use rand_distr::Distribution;
+            let mut weights = self.selectors.iter().map(|z| z.0).collect::<Vec<_>>();
+            // Drop the weight of every selector that has no question, until one provides one.
+            while let Ok(dist) = rand::distributions::WeightedIndex::new(&weights) {
+                let index = dist.sample(&mut self.rng);
+                if let Some(question) = self.selectors[index].1.get_question_filtered(allowed) {
+                    self.last = Some(index);
+                    return Some(question);
                 }
+                weights[index] = 0.0;
             }
+            self.last = None;
+            None
         }
 
-        impl Selector for RecallCurveSelector {
-            fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
-                self.questions.clear();
-                let now = std::time::SystemTime::now();
-                for question in questions.iter() {
-                    let records = recorder
-                        .get_records_by_question(question)
-                        .expect("Should return empty if unknown");
+        fn store_record(&mut self, record: &Record) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.store_record(record);
+            }
+        }
 
-                    let mut last_time = now;
-                    let mut n_t = self.config.n_t_default;
-                    // Now, update n_t based on the past performance.
-                    for record in records.iter() {
-                        last_time = record.time;
-                        if record.score == 1.0 {
-                            // correct.
-                            n_t = (1.0 - self.config.n_t_alpha_correct) * n_t;
-                        } else {
-                            // fail.
-                            n_t = (1.0 + self.config.n_t_beta_incorrect) * n_t;
-                        }
-                    }
+        fn store_partial_credit(&mut self, record: &Record) {
+            for (_, selector) in self.selectors.iter_mut() {
+                selector.store_partial_credit(record);
+            }
+        }
 
-                    self.questions.push(QuestionInfo {
-                        question: *question,
-                        records,
-                        last_time,
-                        n_t,
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            self.selectors
+                .iter()
+                .find_map(|(_, selector)| selector.predict_recall(question, at_time))
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.selectors[self.last?].1.explain()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            snapshot_all(self.selectors.iter().map(|(_, selector)| selector))
+        }
+
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            restore_all(
+                self.selectors.iter_mut().map(|(_, selector)| selector),
+                questions,
+                recorder,
+                state,
+            )
+        }
+    }
+
+    /// Snapshot of several selectors, the selectors that don't support snapshots are left out.
+    fn snapshot_all<'a>(
+        selectors: impl Iterator<Item = &'a Box<dyn Selector>>,
+    ) -> Option<serde_yaml::Value> {
+        let states = selectors.map(|z| z.snapshot()).collect::<Vec<_>>();
+        serde_yaml::to_value(states).ok()
+    }
+
+    /// Restore several selectors from snapshot_all, those without state in the snapshot are set up
+    /// from the records.
+    fn restore_all<'a>(
+        selectors: impl ExactSizeIterator<Item = &'a mut Box<dyn Selector>>,
+        questions: &[Question],
+        recorder: &dyn Recorder,
+        state: &serde_yaml::Value,
+    ) -> Result<(), MemorizerError> {
+        let states: Vec<Option<serde_yaml::Value>> = serde_yaml::from_value(state.clone())?;
+        if states.len() != selectors.len() {
+            return Err("snapshot is for a different number of selectors".into());
+        }
+        for (selector, state) in selectors.zip(states.iter()) {
+            match state {
+                Some(state) => selector.restore(questions, recorder, state)?,
+                None => selector.set_questions(questions, recorder),
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::dummy::DummySelector;
+        use crate::algorithm::super_memo_2::SuperMemo2Selector;
+        use crate::algorithm::{test_clock, test_questions};
+        use crate::recorder::MemoryRecorder;
+
+        #[test]
+        fn test_combinators() {
+            let clock = test_clock();
+            let questions = test_questions(4);
+            let filtered = |learnables: &[u64]| {
+                Box::new(FilterSelector::from_filter(
+                    Box::new(SuperMemo2Selector::default()),
+                    QuestionFilter {
+                        learnables: learnables.iter().map(|v| LearnableId(*v)).collect(),
+                        ..Default::default()
+                    },
+                ))
+            };
+            let answer_all = |selector: &mut dyn Selector| {
+                selector.set_clock(clock.clone());
+                selector.set_rng(crate::rng::SelectorRng::from_seed(0));
+                selector.set_questions(&questions, &MemoryRecorder::new());
+                let mut asked = vec![];
+                while let Some(question) = selector.get_question() {
+                    asked.push(question.learnable.0);
+                    selector.store_record(&Record {
+                        question,
+                        score: 1.0,
+                        time: clock.now(),
                     });
+                    if asked.len() == 10 {
+                        break;
+                    }
                 }
-            }
+                asked.sort();
+                asked
+            };
 
-            /// Retrieve a question to ask.
-            fn get_question(&mut self) -> Option<Question> {
-                // Here, we calculate the review intensity for each question on hand.
-                // then we pick with a weighting.
-                let now = std::time::SystemTime::now();
-                use rand_distr::Distribution;
-                let weights = self
-                    .questions
-                    .iter()
-                    .map(|z| {
-                        let t = now
-                            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                            .expect("can this fail?");
-                        let t_last = z
-                            .last_time
-                            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                            .expect("can this fail?");
-                        let recallt = recall(z.n_t, t.as_secs_f64(), t_last.as_secs_f64());
-                        let reviewt = review_intensity(self.config.q, recallt);
-                        reviewt
-                    })
-                    .collect::<Vec<f64>>();
+            // Only the questions matching the filter are asked.
+            assert_eq!(answer_all(&mut *filtered(&[1, 2])), vec![1, 2]);
 
-                // Create the distribution based on these weights and return the picked index.
-                let dist = rand::distributions::WeightedIndex::new(&weights).unwrap();
-                let index = dist.sample(&mut rand::thread_rng());
+            // Interleaving two filtered selectors asks the questions of both.
+            let mut weighted =
+                WeightedSelector::new(vec![(1.0, filtered(&[0])), (3.0, filtered(&[3]))]);
+            assert_eq!(answer_all(&mut weighted), vec![0, 3]);
 
-                Some(self.questions[index].question)
+            // The fallback keeps asking questions once the primary selector is done.
+            let mut fallback =
+                FallbackSelector::new(filtered(&[2]), Box::new(DummySelector::new()));
+            // That's question 2 from SuperMemo2, followed by the dummy selector cycling through
+            // all questions.
+            assert_eq!(
+                answer_all(&mut fallback),
+                vec![0, 0, 0, 1, 1, 2, 2, 2, 3, 3]
+            );
+        }
+    }
+}
+
+// Cramming, practicing everything regardless of the schedule.
+pub mod cram {
+    use crate::traits::*;
+
+    /// Asks all questions in random order, questions that are not answered well are asked again
+    /// at the end until they are. The history is ignored, this is for practice before an exam.
+    #[derive(Debug)]
+    pub struct CramSelector {
+        /// Questions still to be answered well, in the order they are asked.
+        pending: Vec<Question>,
+        rng: crate::rng::SelectorRng,
+    }
+
+    impl CramSelector {
+        pub fn new() -> Self {
+            CramSelector {
+                pending: vec![],
+                rng: Default::default(),
             }
+        }
+    }
 
-            /// Store answer to a question.
-            fn store_record(&mut self, record: &Record) {
-                // Update the internal record for this question.
-                let z = self
-                    .questions
-                    .iter_mut()
-                    .find(|v| v.question == record.question)
-                    .expect("Passed question for which we don't have a record.");
-                z.records.push(*record);
-                z.last_time = std::time::SystemTime::now();
-                if record.score == 1.0 {
-                    // correct.
-                    z.n_t = (1.0 - self.config.n_t_alpha_correct) * z.n_t;
-                } else {
-                    // fail.
-                    z.n_t = (1.0 + self.config.n_t_beta_incorrect) * z.n_t;
+    impl Default for CramSelector {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Selector for CramSelector {
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.rng = rng;
+        }
+
+        fn set_questions(&mut self, questions: &[Question], _recorder: &dyn Recorder) {
+            use rand::seq::SliceRandom;
+            self.pending = questions.to_vec();
+            self.pending.shuffle(&mut self.rng);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            self.pending.iter().find(|q| allowed(q)).copied()
+        }
+
+        /// Store answer to a question, it is done if answered well, otherwise it moves to the end.
+        fn store_record(&mut self, record: &Record) {
+            if let Some(index) = self.pending.iter().position(|q| *q == record.question) {
+                let question = self.pending.remove(index);
+                // Same as the re-review in SuperMemo2, a grade of 4 or more is good enough.
+                if super::super_memo_2::score_to_grade(record.score) < 4 {
+                    self.pending.push(question);
                 }
             }
         }
     }
 }
+
+// Detection of leeches, questions that keep being failed.
+pub mod leech {
+    use crate::traits::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct LeechConfig {
+        /// Number of lapses after which a question is a leech, see [`count_failures`].
+        pub failures: usize,
+        /// Whether leeches are suspended, such that they are no longer asked.
+        pub suspend: bool,
+    }
+
+    impl Default for LeechConfig {
+        fn default() -> LeechConfig {
+            LeechConfig {
+                failures: 8,
+                suspend: false,
+            }
+        }
+    }
+
+    /// Whether the answer with this score is a failure.
+    pub fn is_failure(score: Score) -> bool {
+        super::super_memo_2::score_to_grade(score) < 3
+    }
+
+    /// Failures per question, with the day of the last one counted.
+    #[derive(Debug, Default)]
+    struct Lapses {
+        counts: HashMap<Question, (usize, i64)>,
+    }
+
+    impl Lapses {
+        fn add(&mut self, boundary: &crate::clock::DayBoundary, record: &Record) {
+            if !is_failure(record.score) {
+                return;
+            }
+            let day = boundary.day(record.time);
+            let (count, last_day) = self.counts.entry(record.question).or_insert((0, day - 1));
+            if *last_day != day {
+                *count += 1;
+                *last_day = day;
+            }
+        }
+
+        fn count(&self, question: &Question) -> usize {
+            self.counts.get(question).map(|c| c.0).unwrap_or(0)
+        }
+    }
+
+    /// Count the lapses for each question, the days on which it was failed. Failing it again
+    /// the same day, while relearning it, doesn't count. The records must be in order of time.
+    pub fn count_failures(
+        records: &[Record],
+        boundary: &crate::clock::DayBoundary,
+    ) -> HashMap<Question, usize> {
+        let mut lapses = Lapses::default();
+        for record in records.iter() {
+            lapses.add(boundary, record);
+        }
+        lapses.counts.into_iter().map(|(q, c)| (q, c.0)).collect()
+    }
+
+    /// The questions that are leeches according to the records, in the order of the questions.
+    pub fn find_leeches(
+        questions: &[Question],
+        records: &[Record],
+        config: &LeechConfig,
+        boundary: &crate::clock::DayBoundary,
+    ) -> Vec<Question> {
+        let failures = count_failures(records, boundary);
+        questions
+            .iter()
+            .filter(|q| failures.get(q).copied().unwrap_or(0) >= config.failures)
+            .copied()
+            .collect()
+    }
+
+    /// Wraps another selector and tracks the leeches, these are no longer asked if they are to
+    /// be suspended. The answers to leeches are still passed to the wrapped selector.
+    #[derive(Debug)]
+    pub struct LeechSelector {
+        selector: Box<dyn Selector>,
+        config: LeechConfig,
+        failures: Lapses,
+        day_boundary: crate::clock::DayBoundary,
+    }
+
+    impl LeechSelector {
+        pub fn new(selector: Box<dyn Selector>, config: LeechConfig) -> Self {
+            LeechSelector {
+                selector,
+                config,
+                failures: Default::default(),
+                day_boundary: Default::default(),
+            }
+        }
+
+        fn is_leech(failures: &Lapses, config: &LeechConfig, question: &Question) -> bool {
+            failures.count(question) >= config.failures
+        }
+
+        fn count_failures(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.failures = Lapses::default();
+            let mut records = crate::algorithm::history(recorder, questions);
+            records.sort_by_key(|r| r.time);
+            for record in records.iter() {
+                self.failures.add(&self.day_boundary, record);
+            }
+        }
+    }
+
+    impl Selector for LeechSelector {
+        fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+            self.selector.set_clock(clock);
+        }
+
+        fn set_rng(&mut self, rng: crate::rng::SelectorRng) {
+            self.selector.set_rng(rng);
+        }
+
+        fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+            self.day_boundary = boundary;
+            self.selector.set_day_boundary(boundary);
+        }
+
+        fn set_questions(&mut self, questions: &[Question], recorder: &dyn Recorder) {
+            self.set_questions_with_credit(questions, recorder, &[]);
+        }
+
+        fn set_questions_with_credit(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            credit: &[Record],
+        ) {
+            self.count_failures(questions, recorder);
+            self.selector
+                .set_questions_with_credit(questions, recorder, credit);
+        }
+
+        fn get_question(&mut self) -> Option<Question> {
+            self.get_question_filtered(&|_| true)
+        }
+
+        /// Retrieve a question from the wrapped selector, leaving out the leeches if these are
+        /// suspended.
+        fn get_question_filtered(
+            &mut self,
+            allowed: &dyn Fn(&Question) -> bool,
+        ) -> Option<Question> {
+            if !self.config.suspend {
+                return self.selector.get_question_filtered(allowed);
+            }
+            let failures = &self.failures;
+            let config = &self.config;
+            self.selector
+                .get_question_filtered(&|q| allowed(q) && !Self::is_leech(failures, config, q))
+        }
+
+        fn store_record(&mut self, record: &Record) {
+            self.failures.add(&self.day_boundary, record);
+            self.selector.store_record(record);
+        }
+
+        fn store_partial_credit(&mut self, record: &Record) {
+            self.selector.store_partial_credit(record);
+        }
+
+        fn predict_recall(
+            &self,
+            question: &Question,
+            at_time: std::time::SystemTime,
+        ) -> Option<f64> {
+            self.selector.predict_recall(question, at_time)
+        }
+
+        fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+            self.selector.forecast(days)
+        }
+
+        fn progress(&self) -> Option<Progress> {
+            self.selector.progress()
+        }
+
+        fn explain(&self) -> Option<Explanation> {
+            self.selector.explain()
+        }
+
+        fn snapshot(&self) -> Option<serde_yaml::Value> {
+            super::snapshot_tagged("Leech", &self.config, &self.selector.snapshot()?)
+        }
+
+        /// Restore the wrapped selector, the failures are counted from the records as these
+        /// don't need to be replayed.
+        fn restore(
+            &mut self,
+            questions: &[Question],
+            recorder: &dyn Recorder,
+            state: &serde_yaml::Value,
+        ) -> Result<(), MemorizerError> {
+            let state: serde_yaml::Value = super::restore_tagged("Leech", &self.config, state)?;
+            self.selector.restore(questions, recorder, &state)?;
+            self.count_failures(questions, recorder);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::algorithm::dummy::DummySelector;
+        use crate::algorithm::test_questions;
+        use crate::recorder::MemoryRecorder;
+
+        #[test]
+        fn test_leech() {
+            let questions = test_questions(2);
+            let config = LeechConfig {
+                failures: 3,
+                suspend: true,
+            };
+            let mut selector = LeechSelector::new(Box::new(DummySelector::new()), config.clone());
+            selector.set_questions(&questions, &MemoryRecorder::new());
+
+            // Keep failing the first question, answer the other one well, one answer a day.
+            let mut records = vec![];
+            for day in 0..10 {
+                let Some(question) = selector.get_question() else {
+                    break;
+                };
+                let score = if question == questions[0] { 0.2 } else { 1.0 };
+                let record = Record {
+                    question,
+                    score,
+                    time: std::time::SystemTime::UNIX_EPOCH
+                        + std::time::Duration::from_secs(day * 24 * 60 * 60),
+                };
+                selector.store_record(&record);
+                records.push(record);
+            }
+
+            // After three failures the first question is no longer asked.
+            assert_eq!(
+                records
+                    .iter()
+                    .filter(|r| r.question == questions[0])
+                    .count(),
+                3
+            );
+            assert_eq!(
+                find_leeches(&questions, &records, &config, &Default::default()),
+                vec![questions[0]]
+            );
+        }
+
+        #[test]
+        fn test_lapses_per_day() {
+            let question = Question::default();
+            let boundary =
+                crate::clock::DayBoundary::local(chrono_tz::UTC, "04:00".parse().unwrap());
+            let day = std::time::Duration::from_secs(24 * 60 * 60);
+            let hour = std::time::Duration::from_secs(60 * 60);
+            let start = std::time::SystemTime::UNIX_EPOCH + day * 1000 + hour * 10;
+            let fail = |time| Record {
+                question,
+                score: 0.0,
+                time,
+            };
+
+            // Failing it again while relearning it the same day counts once, a failure the next
+            // day counts again, also shortly after the start of the day.
+            let records = [
+                fail(start),
+                fail(start + hour),
+                fail(start + hour * 2),
+                fail(start + hour * 19),
+            ];
+            assert_eq!(count_failures(&records, &boundary)[&question], 2);
+            assert_eq!(count_failures(&records[..3], &boundary)[&question], 1);
+
+            // The selector counts the same as the records.
+            let mut selector = LeechSelector::new(
+                Box::new(DummySelector::new()),
+                LeechConfig {
+                    failures: 2,
+                    suspend: true,
+                },
+            );
+            selector.set_day_boundary(boundary);
+            selector.set_questions(&[question], &MemoryRecorder::new());
+            for record in records[..3].iter() {
+                selector.store_record(record);
+            }
+            assert_eq!(selector.get_question(), Some(question));
+            selector.store_record(&records[3]);
+            assert_eq!(selector.get_question(), None);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// Implementations of the Clock trait.
+
+use crate::traits::{Clock, MemorizerError};
+use serde::{Deserialize, Serialize};
+
+/// Clock that provides the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> std::time::SystemTime {
+        std::time::SystemTime::now()
+    }
+}
+
+/// Clock that only moves when it is told to, for tests and simulations.
+#[derive(Debug)]
+pub struct ManualClock {
+    time: std::sync::Mutex<std::time::SystemTime>,
+}
+
+impl ManualClock {
+    /// Create a clock that is stopped at the provided time.
+    pub fn new(time: std::time::SystemTime) -> Self {
+        ManualClock {
+            time: std::sync::Mutex::new(time),
+        }
+    }
+
+    /// Set the current time.
+    pub fn set(&self, time: std::time::SystemTime) {
+        *self.time.lock().expect("not poisoned") = time;
+    }
+
+    /// Move the current time forward by duration.
+    pub fn advance(&self, duration: std::time::Duration) {
+        *self.time.lock().expect("not poisoned") += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> std::time::SystemTime {
+        *self.time.lock().expect("not poisoned")
+    }
+}
+
+/// Time of day, as hours and minutes. Written as "HH:MM" in configuration files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl TimeOfDay {
+    /// Seconds since midnight.
+    fn seconds(&self) -> i64 {
+        (self.hour as i64 * 60 + self.minute as i64) * 60
+    }
+}
+
+impl std::str::FromStr for TimeOfDay {
+    type Err = MemorizerError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hour, minute) = s
+            .split_once(':')
+            .ok_or(format!("time of day {s:?} is not formatted as HH:MM"))?;
+        let hour = hour.trim().parse::<u32>()?;
+        let minute = minute.trim().parse::<u32>()?;
+        if hour >= 24 || minute >= 60 {
+            return Err(format!("time of day {s:?} is out of range").into());
+        }
+        Ok(TimeOfDay { hour, minute })
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = MemorizerError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(t: TimeOfDay) -> String {
+        format!("{:02}:{:02}", t.hour, t.minute)
+    }
+}
+
+/// Where one day ends and the next one starts, this determines when a question with an interval
+/// in days becomes due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayBoundary {
+    /// Days are 24 hour periods after the last answer. Six hours are subtracted from intervals,
+    /// this allows for testing at an earlier timestamp than exactly 24 hours for a day, preventing
+    /// the interval from 'moving forward' in time when reviewing at roughly the same time each day.
+    /// Day numbers, used for daily limits, are UTC days.
+    #[default]
+    Rolling,
+    /// Days are local days in the timezone, starting at the time of day. Due times snap to the
+    /// start of a day, such that reviewing at a different time each day doesn't push the next
+    /// review forward, and a question due on a day is due as soon as that day starts. The offset
+    /// from UTC follows the timezone, so days are 23 or 25 hours long on daylight saving time
+    /// transitions.
+    Local {
+        /// IANA name of the timezone, for example "Europe/Amsterdam".
+        timezone: chrono_tz::Tz,
+        /// Local time at which a new day starts, answers before this time count towards the
+        /// previous day.
+        day_start: TimeOfDay,
+    },
+}
+
+impl DayBoundary {
+    /// Slack subtracted from intervals by [`DayBoundary::Rolling`].
+    const ROLLING_SLACK: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+    /// Local days in the provided timezone starting at the time of day.
+    pub fn local(timezone: chrono_tz::Tz, day_start: TimeOfDay) -> Self {
+        DayBoundary::Local {
+            timezone,
+            day_start,
+        }
+    }
+
+    /// Number of the day this time falls in.
+    pub fn day(&self, time: std::time::SystemTime) -> i64 {
+        let utc = chrono::DateTime::<chrono::Utc>::from(time);
+        match self {
+            DayBoundary::Rolling => utc.timestamp().div_euclid(SECONDS_PER_DAY),
+            DayBoundary::Local {
+                timezone,
+                day_start,
+            } => {
+                let local = utc.with_timezone(timezone).naive_local();
+                let shifted = local - chrono::TimeDelta::seconds(day_start.seconds());
+                shifted
+                    .date()
+                    .signed_duration_since(chrono::NaiveDate::default())
+                    .num_days()
+            }
+        }
+    }
+
+    /// The moment the provided day starts.
+    pub fn start_of_day(&self, day: i64) -> std::time::SystemTime {
+        let utc = match self {
+            DayBoundary::Rolling => {
+                chrono::DateTime::from_timestamp(day * SECONDS_PER_DAY, 0).unwrap_or_default()
+            }
+            DayBoundary::Local {
+                timezone,
+                day_start,
+            } => {
+                use chrono::TimeZone;
+                let start = (chrono::NaiveDate::default() + chrono::TimeDelta::days(day))
+                    .and_time(chrono::NaiveTime::MIN)
+                    + chrono::TimeDelta::seconds(day_start.seconds());
+                // If the day start falls in the gap of a daylight saving time transition, it moves
+                // forward by the length of the gap. On a repeated hour it is the first one.
+                (0..=24)
+                    .find_map(|h| {
+                        timezone
+                            .from_local_datetime(&(start + chrono::TimeDelta::hours(h)))
+                            .earliest()
+                    })
+                    .map(|t| t.to_utc())
+                    .unwrap_or_else(|| start.and_utc())
+            }
+        };
+        utc.into()
+    }
+
+    /// The moment a question with the provided interval becomes due, when it was last answered at
+    /// time. Intervals are rounded to whole days for [`DayBoundary::Local`].
+    pub fn due_after(
+        &self,
+        time: std::time::SystemTime,
+        interval: std::time::Duration,
+    ) -> std::time::SystemTime {
+        match self {
+            DayBoundary::Rolling => time + interval.saturating_sub(Self::ROLLING_SLACK),
+            DayBoundary::Local { .. } => {
+                let days = (interval.as_secs_f64() / SECONDS_PER_DAY as f64).round();
+                self.start_of_day(self.day(time) + days as i64)
+            }
+        }
+    }
+
+    /// The moment the provided number of days after time starts, see [`DayBoundary::due_after`].
+    pub fn days_after(&self, time: std::time::SystemTime, days: u64) -> std::time::SystemTime {
+        let day = std::time::Duration::from_secs(SECONDS_PER_DAY as u64);
+        self.due_after(time, day * days as u32)
+    }
+
+    /// Number of whole days from a to b, zero if b is not later. Local days are counted for
+    /// [`DayBoundary::Local`], rounded periods of 24 hours for [`DayBoundary::Rolling`].
+    pub fn days_between(&self, a: std::time::SystemTime, b: std::time::SystemTime) -> u64 {
+        match self {
+            DayBoundary::Rolling => {
+                let elapsed = b.duration_since(a).unwrap_or_default().as_secs_f64();
+                (elapsed / SECONDS_PER_DAY as f64).round() as u64
+            }
+            DayBoundary::Local { .. } => (self.day(b) - self.day(a)).max(0) as u64,
+        }
+    }
+
+    /// Whether both times fall on the same day.
+    pub fn same_day(&self, a: std::time::SystemTime, b: std::time::SystemTime) -> bool {
+        self.day(a) == self.day(b)
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_boundary() {
+        let hour = std::time::Duration::from_secs(60 * 60);
+        // 2023-03-25 00:00 UTC, the day before daylight saving time starts in Europe.
+        let midnight = std::time::UNIX_EPOCH + hour * 24 * 19441;
+
+        // Rolling days keep the six hours of slack.
+        let rolling = DayBoundary::Rolling;
+        assert_eq!(rolling.days_after(midnight, 1), midnight + hour * 18);
+        assert_eq!(rolling.days_after(midnight, 0), midnight);
+        assert_eq!(rolling.days_between(midnight, midnight + hour * 20), 1);
+
+        // Days start at 04:30 in Amsterdam, that is 03:30 UTC in winter and 02:30 UTC in summer.
+        let boundary = DayBoundary::local(chrono_tz::Europe::Amsterdam, "04:30".parse().unwrap());
+        assert!(boundary.same_day(midnight, midnight + hour * 3));
+        assert!(!boundary.same_day(midnight, midnight + hour * 4));
+        assert_eq!(
+            boundary.days_after(midnight, 1),
+            midnight + hour * 3 + hour / 2
+        );
+        assert_eq!(
+            boundary.days_after(midnight + hour * 4, 1),
+            midnight + hour * 26 + hour / 2
+        );
+        assert_eq!(boundary.days_between(midnight, midnight + hour * 4), 1);
+
+        // A day start in the skipped hour moves forward by an hour, 03:30 CEST is 01:30 UTC.
+        let boundary = DayBoundary::local(chrono_tz::Europe::Amsterdam, "02:30".parse().unwrap());
+        assert_eq!(
+            boundary.days_after(midnight + hour * 12, 1),
+            midnight + hour * 25 + hour / 2
+        );
+
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+    }
+}
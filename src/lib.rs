@@ -14,8 +14,20 @@ pub mod text;
 /// Simple implementation to keep records
 pub mod recorder;
 
+/// Clocks to obtain the current time from.
+pub mod clock;
+
+/// Random number generator for selectors.
+pub mod rng;
+
 /// Algorithm things.
 pub mod algorithm;
 
 /// Implementor for a training loop.
 pub mod training;
+
+/// Fitting selector parameters to past records.
+pub mod optimize;
+
+/// Simulation of a learner to compare selectors.
+pub mod simulation;
@@ -0,0 +1,195 @@
+// Fitting selector parameters to the history in a recorder.
+
+use crate::algorithm::memorize::recall;
+use crate::algorithm::memorize::recall_curve::RecallCurveConfig;
+use crate::traits::*;
+
+/// Smallest probability used in the likelihood, prevents taking the logarithm of zero.
+const PROBABILITY_EPSILON: f64 = 1e-9;
+
+/// Minimize a function with the Nelder-Mead simplex method, starting at x0. Returns the best
+/// point found after the provided number of iterations.
+pub fn nelder_mead(f: impl Fn(&[f64]) -> f64, x0: &[f64], iterations: usize) -> Vec<f64> {
+    const REFLECTION: f64 = 1.0;
+    const EXPANSION: f64 = 2.0;
+    const CONTRACTION: f64 = 0.5;
+    const SHRINK: f64 = 0.5;
+
+    let n = x0.len();
+    // Initial simplex, x0 and a step along each axis.
+    let mut simplex: Vec<(Vec<f64>, f64)> = vec![(x0.to_vec(), f(x0))];
+    for i in 0..n {
+        let mut x = x0.to_vec();
+        x[i] += if x[i] == 0.0 { 0.1 } else { 0.1 * x[i].abs() };
+        let v = f(&x);
+        simplex.push((x, v));
+    }
+
+    let combine = |a: &[f64], b: &[f64], t: f64| -> Vec<f64> {
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| a + t * (b - a))
+            .collect()
+    };
+
+    for _ in 0..iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        // Centroid of all but the worst point.
+        let mut centroid = vec![0.0; n];
+        for (x, _) in simplex.iter().take(n) {
+            for (c, v) in centroid.iter_mut().zip(x.iter()) {
+                *c += v / n as f64;
+            }
+        }
+
+        let worst = simplex[n].clone();
+        let reflected = combine(&centroid, &worst.0, -REFLECTION);
+        let reflected_v = f(&reflected);
+
+        if reflected_v < simplex[0].1 {
+            let expanded = combine(&centroid, &worst.0, -EXPANSION);
+            let expanded_v = f(&expanded);
+            simplex[n] = if expanded_v < reflected_v {
+                (expanded, expanded_v)
+            } else {
+                (reflected, reflected_v)
+            };
+        } else if reflected_v < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_v);
+        } else {
+            let contracted = combine(&centroid, &worst.0, CONTRACTION);
+            let contracted_v = f(&contracted);
+            if contracted_v < worst.1 {
+                simplex[n] = (contracted, contracted_v);
+            } else {
+                // Shrink everything towards the best point.
+                let best = simplex[0].0.clone();
+                for entry in simplex.iter_mut().skip(1) {
+                    let x = combine(&best, &entry.0, SHRINK);
+                    let v = f(&x);
+                    *entry = (x, v);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0).0
+}
+
+/// Group records by question, each group sorted by time.
+pub fn records_by_question(records: &[Record]) -> Vec<Vec<Record>> {
+    let mut grouped: std::collections::HashMap<Question, Vec<Record>> = Default::default();
+    for record in records.iter() {
+        grouped.entry(record.question).or_default().push(*record);
+    }
+    let mut res = grouped.into_values().collect::<Vec<_>>();
+    for v in res.iter_mut() {
+        v.sort_by_key(|r| r.time);
+    }
+    res
+}
+
+/// Log likelihood of the observed scores under the forgetting curve of the recall curve selector.
+/// The score is treated as the observed probability of recall, the first record of each question
+/// only initialises the forgetting rate as there is no previous review to decay from.
+pub fn recall_curve_log_likelihood(grouped: &[Vec<Record>], config: &RecallCurveConfig) -> f64 {
+    let mut total = 0.0;
+    for records in grouped.iter() {
+        let mut n_t = config.n_t_default;
+        let mut last_time = None;
+        for record in records.iter() {
+            if let Some(last_time) = last_time {
+                let dt = record
+                    .time
+                    .duration_since(last_time)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                let m = recall(n_t, dt, 0.0).clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+                total += record.score * m.ln() + (1.0 - record.score) * (1.0 - m).ln();
+            }
+            n_t = config.update_n_t(n_t, record.score);
+            last_time = Some(record.time);
+        }
+    }
+    total
+}
+
+/// Fit the forgetting rate parameters of the recall curve configuration by maximizing the
+/// likelihood of the records. The review rate q is taken from the initial configuration.
+pub fn fit_recall_curve(records: &[Record], initial: &RecallCurveConfig) -> RecallCurveConfig {
+    let grouped = records_by_question(records);
+
+    // Optimize in an unconstrained space; alpha is in (0, 1), beta and n_t_default are positive.
+    let logit = |p: f64| (p / (1.0 - p)).ln();
+    let sigmoid = |x: f64| 1.0 / (1.0 + (-x).exp());
+    let to_config = |x: &[f64]| RecallCurveConfig {
+        n_t_alpha_correct: sigmoid(x[0]),
+        n_t_beta_incorrect: x[1].exp(),
+        n_t_default: x[2].exp(),
+        ..initial.clone()
+    };
+    let x0 = [
+        logit(initial.n_t_alpha_correct),
+        initial.n_t_beta_incorrect.ln(),
+        initial.n_t_default.ln(),
+    ];
+
+    let best = nelder_mead(
+        |x| -recall_curve_log_likelihood(&grouped, &to_config(x)),
+        &x0,
+        500,
+    );
+    to_config(&best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nelder_mead() {
+        let f = |x: &[f64]| (x[0] - 3.0).powi(2) + (x[1] + 1.0).powi(2);
+        let best = nelder_mead(f, &[0.0, 0.0], 200);
+        assert!((best[0] - 3.0).abs() < 1e-3);
+        assert!((best[1] + 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_recall_curve() {
+        // A question that is always recalled after a day, and one that is always forgotten.
+        let start = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        let easy = Question {
+            learnable: LearnableId(0),
+            ..Default::default()
+        };
+        let hard = Question {
+            learnable: LearnableId(1),
+            ..Default::default()
+        };
+        let mut records = vec![];
+        for i in 0..10 {
+            let time = start + day * i;
+            records.push(Record {
+                question: easy,
+                score: 1.0,
+                time,
+            });
+            records.push(Record {
+                question: hard,
+                score: 0.0,
+                time,
+            });
+        }
+
+        let initial = RecallCurveConfig::default();
+        let fitted = fit_recall_curve(&records, &initial);
+        let grouped = records_by_question(&records);
+        assert!(
+            recall_curve_log_likelihood(&grouped, &fitted)
+                > recall_curve_log_likelihood(&grouped, &initial)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 // Just a simple implementation for the Recorder trait.
 
-use crate::traits::{MemorizerError, Question, Record, Recorder};
+use crate::traits::{MemorizerError, Question, Record, Recorder, SelectorSnapshot, Suspension};
 use serde::{Deserialize, Serialize};
 
 /// Recorder that only keeps all records in memory, but it is (de)serializable to easily allow
@@ -8,15 +8,25 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct MemoryRecorder {
     records: Vec<Record>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    suspensions: Vec<Suspension>,
+    #[serde(skip)]
+    snapshot: Option<SelectorSnapshot>,
 }
 
 impl MemoryRecorder {
     pub fn new() -> Self {
-        MemoryRecorder { records: vec![] }
+        MemoryRecorder {
+            records: vec![],
+            suspensions: vec![],
+            snapshot: None,
+        }
     }
     pub fn from(records: &[Record]) -> Self {
         MemoryRecorder {
             records: records.to_vec(),
+            suspensions: vec![],
+            snapshot: None,
         }
     }
 }
@@ -32,12 +42,46 @@ impl Recorder for MemoryRecorder {
             .records
             .iter()
             .filter(|z| z.question == *question)
-            .map(|z| *z)
+            .copied()
             .collect::<_>())
     }
+
+    fn get_records(&self) -> Result<Vec<Record>, MemorizerError> {
+        Ok(self.records.clone())
+    }
+
+    fn store_suspension(&mut self, suspension: &Suspension) -> Result<(), MemorizerError> {
+        self.suspensions
+            .retain(|z| z.question != suspension.question);
+        self.suspensions.push(*suspension);
+        Ok(())
+    }
+
+    fn remove_suspension(&mut self, question: &Question) -> Result<(), MemorizerError> {
+        self.suspensions.retain(|z| z.question != *question);
+        Ok(())
+    }
+
+    fn get_suspensions(&self) -> Result<Vec<Suspension>, MemorizerError> {
+        Ok(self.suspensions.clone())
+    }
+
+    fn record_count(&self) -> Result<usize, MemorizerError> {
+        Ok(self.records.len())
+    }
+
+    fn store_snapshot(&mut self, snapshot: &SelectorSnapshot) -> Result<(), MemorizerError> {
+        self.snapshot = Some(snapshot.clone());
+        Ok(())
+    }
+
+    fn get_snapshot(&self) -> Result<Option<SelectorSnapshot>, MemorizerError> {
+        Ok(self.snapshot.clone())
+    }
 }
 
-/// A recorder that read and writes a yaml file.
+/// A recorder that read and writes a yaml file. The snapshot of the selector state is kept in a
+/// second file next to it, with the extension `snapshot.yaml`.
 #[derive(Debug)]
 pub struct YamlRecorder {
     recorder: MemoryRecorder,
@@ -48,15 +92,22 @@ impl YamlRecorder {
     /// will load data from there when created.
     pub fn new(filename: &std::path::Path) -> Result<Self, MemorizerError> {
         // Read from file if it exists, else empty.
-        let recorder: MemoryRecorder;
-
-        if std::path::Path::new(filename).exists() {
+        let mut recorder: MemoryRecorder = if std::path::Path::new(filename).exists() {
             let file = std::fs::File::open(filename)
                 .map_err(|e| format!("failed to open {filename:?}: {e:?}"))?;
             let yaml: serde_yaml::Value = serde_yaml::from_reader(file)?;
-            recorder = serde_yaml::from_value(yaml)?;
+            serde_yaml::from_value(yaml)?
         } else {
-            recorder = Default::default();
+            Default::default()
+        };
+
+        // The snapshot is only used to speed up setting up the selector, if it can't be read the
+        // selector is set up from the records instead.
+        let snapshot_filename = Self::snapshot_filename(filename);
+        if let Ok(file) = std::fs::File::open(&snapshot_filename) {
+            if let Ok(snapshot) = serde_yaml::from_reader(file) {
+                recorder.store_snapshot(&snapshot)?;
+            }
         }
 
         Ok(YamlRecorder {
@@ -65,6 +116,11 @@ impl YamlRecorder {
         })
     }
 
+    /// The file holding the snapshot for the recorder stored in filename.
+    fn snapshot_filename(filename: &std::path::Path) -> std::path::PathBuf {
+        filename.with_extension("snapshot.yaml")
+    }
+
     /// Write the data to the disk.
     pub fn write(&mut self) -> Result<(), MemorizerError> {
         use std::fs::OpenOptions;
@@ -93,4 +149,39 @@ impl Recorder for YamlRecorder {
     fn get_records_by_question(&self, question: &Question) -> Result<Vec<Record>, MemorizerError> {
         self.recorder.get_records_by_question(question)
     }
+
+    fn get_records(&self) -> Result<Vec<Record>, MemorizerError> {
+        self.recorder.get_records()
+    }
+
+    fn store_suspension(&mut self, suspension: &Suspension) -> Result<(), MemorizerError> {
+        self.recorder.store_suspension(suspension)?;
+        self.write()
+    }
+
+    fn remove_suspension(&mut self, question: &Question) -> Result<(), MemorizerError> {
+        self.recorder.remove_suspension(question)?;
+        self.write()
+    }
+
+    fn get_suspensions(&self) -> Result<Vec<Suspension>, MemorizerError> {
+        self.recorder.get_suspensions()
+    }
+
+    fn record_count(&self) -> Result<usize, MemorizerError> {
+        self.recorder.record_count()
+    }
+
+    fn store_snapshot(&mut self, snapshot: &SelectorSnapshot) -> Result<(), MemorizerError> {
+        self.recorder.store_snapshot(snapshot)?;
+        let filename = Self::snapshot_filename(&self.filename);
+        let file = std::fs::File::create(&filename)
+            .map_err(|e| format!("failed to write to {filename:?}: {e:?}"))?;
+        serde_yaml::to_writer(file, snapshot)?;
+        Ok(())
+    }
+
+    fn get_snapshot(&self) -> Result<Option<SelectorSnapshot>, MemorizerError> {
+        self.recorder.get_snapshot()
+    }
 }
@@ -0,0 +1,63 @@
+// Random number generation for selectors.
+
+/// Random number generator used by the selectors. Wraps any RngCore, such that sessions can be
+/// reproduced by seeding it.
+pub struct SelectorRng {
+    rng: Box<dyn rand::RngCore + Send + Sync>,
+}
+
+impl SelectorRng {
+    /// Use the provided random number generator.
+    pub fn new(rng: impl rand::RngCore + Send + Sync + 'static) -> Self {
+        SelectorRng { rng: Box::new(rng) }
+    }
+
+    /// Generator seeded from the operating system, different for every run.
+    pub fn from_entropy() -> Self {
+        use rand::SeedableRng;
+        Self::new(rand::rngs::StdRng::from_entropy())
+    }
+
+    /// Generator with a fixed seed, the same seed results in the same sequence.
+    pub fn from_seed(seed: u64) -> Self {
+        use rand::SeedableRng;
+        Self::new(rand::rngs::StdRng::seed_from_u64(seed))
+    }
+
+    /// A new generator seeded from this one, for selectors that pass generators on to the
+    /// selectors they wrap.
+    pub fn split(&mut self) -> Self {
+        use rand::RngCore;
+        Self::from_seed(self.next_u64())
+    }
+}
+
+impl Default for SelectorRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl std::fmt::Debug for SelectorRng {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SelectorRng").finish_non_exhaustive()
+    }
+}
+
+impl rand::RngCore for SelectorRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,264 @@
+// Simulation of a learner, to compare selectors without waiting months for real results.
+
+use crate::clock::ManualClock;
+use crate::recorder::MemoryRecorder;
+use crate::rng::SelectorRng;
+use crate::training::Training;
+use crate::traits::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// The synthetic student, each question is forgotten exponentially with its own forgetting rate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StudentConfig {
+    /// Median forgetting rate per day of a question that was just learned.
+    pub forgetting_rate: f64,
+    /// Standard deviation of the log of the forgetting rate, spreads the difficulty of questions.
+    pub difficulty_spread: f64,
+    /// Factor applied to the forgetting rate after a successful recall.
+    pub success_factor: f64,
+    /// Factor applied to the forgetting rate after a failed recall.
+    pub failure_factor: f64,
+}
+
+impl Default for StudentConfig {
+    fn default() -> StudentConfig {
+        StudentConfig {
+            forgetting_rate: 0.5,
+            difficulty_spread: 0.5,
+            success_factor: 0.5,
+            failure_factor: 1.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SimulationConfig {
+    /// The student being simulated.
+    pub student: StudentConfig,
+    /// Number of days to simulate, there is one session per day.
+    pub days: usize,
+    /// Upper limit on the questions per session, for selectors that never end the session.
+    pub max_reviews_per_day: usize,
+    /// Time the student spends on each question, in seconds.
+    pub seconds_per_review: f64,
+    /// Seed for the student and the selector.
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> SimulationConfig {
+        SimulationConfig {
+            student: Default::default(),
+            days: 30,
+            max_reviews_per_day: 200,
+            seconds_per_review: 10.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Metrics for a single simulated day.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DayReport {
+    /// The day, starting at zero.
+    pub day: usize,
+    /// Number of questions asked.
+    pub reviews: usize,
+    /// Number of questions asked for the first time.
+    pub new_questions: usize,
+    /// Number of questions recalled correctly.
+    pub correct: usize,
+    /// True probability of recall averaged over all questions seen so far, at the end of the day.
+    pub retention: f64,
+}
+
+/// Result of a simulation.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimulationReport {
+    /// Metrics for each day.
+    pub days: Vec<DayReport>,
+    /// Total number of questions asked, the workload.
+    pub total_reviews: usize,
+    /// Average number of questions asked per day.
+    pub reviews_per_day: f64,
+    /// Retention at the end of the last day.
+    pub final_retention: f64,
+    /// Retention averaged over all days.
+    pub average_retention: f64,
+}
+
+#[derive(Debug, Clone)]
+struct StudentMemory {
+    forgetting_rate: f64,
+    last_review: Option<std::time::SystemTime>,
+}
+
+impl StudentMemory {
+    /// Probability of recall at time now, zero for a question never seen.
+    fn recall(&self, now: std::time::SystemTime) -> f64 {
+        if let Some(last_review) = self.last_review {
+            let days = now
+                .duration_since(last_review)
+                .unwrap_or_default()
+                .as_secs_f64()
+                / SECONDS_PER_DAY;
+            (-self.forgetting_rate * days).exp()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Simulate a student learning the learnables with the provided selector. The student model,
+/// clock and the selector's random number generator are all controlled by the simulation, so the
+/// result only depends on the inputs.
+pub fn simulate(
+    learnables: Vec<Box<dyn Learnable>>,
+    mut selector: Box<dyn Selector>,
+    config: &SimulationConfig,
+) -> SimulationReport {
+    use rand::{Rng, SeedableRng};
+    use rand_distr::Distribution;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(config.seed);
+    selector.set_rng(SelectorRng::from_seed(rng.gen()));
+
+    // Each question gets its own difficulty.
+    let difficulty = rand_distr::LogNormal::new(
+        config.student.forgetting_rate.ln(),
+        config.student.difficulty_spread,
+    )
+    .expect("valid difficulty spread");
+    // Kept in a vector to sum in a fixed order, the index maps questions into it.
+    let mut memory: Vec<StudentMemory> = vec![];
+    let mut index: HashMap<Question, usize> = Default::default();
+    for learnable in learnables.iter() {
+        for question in learnable.edges() {
+            index.insert(question, memory.len());
+            memory.push(StudentMemory {
+                forgetting_rate: difficulty.sample(&mut rng),
+                last_review: None,
+            });
+        }
+    }
+
+    // Start at an arbitrary but fixed moment, the first session of each day is at its start.
+    let start = std::time::SystemTime::UNIX_EPOCH
+        + std::time::Duration::from_secs_f64(20000.0 * SECONDS_PER_DAY);
+    let clock = Arc::new(ManualClock::new(start));
+    let mut training = Training::with_clock(
+        learnables,
+        Box::new(MemoryRecorder::new()),
+        selector,
+        clock.clone(),
+    )
+    .expect("memory recorder provides suspensions");
+
+    let review_duration = std::time::Duration::from_secs_f64(config.seconds_per_review);
+    let mut days = vec![];
+    for day in 0..config.days {
+        clock.set(start + std::time::Duration::from_secs_f64(day as f64 * SECONDS_PER_DAY));
+        let mut report = DayReport {
+            day,
+            reviews: 0,
+            new_questions: 0,
+            correct: 0,
+            retention: 0.0,
+        };
+
+        while report.reviews < config.max_reviews_per_day {
+            let question = if let Some(question) = training.question() {
+                question
+            } else {
+                break;
+            };
+            let now = training.now();
+            let state = &mut memory[*index
+                .get(&question)
+                .expect("selector only provides known questions")];
+            if state.last_review.is_none() {
+                report.new_questions += 1;
+            }
+
+            let recalled = rng.gen::<f64>() < state.recall(now);
+            if recalled {
+                report.correct += 1;
+                state.forgetting_rate *= config.student.success_factor;
+            } else if state.last_review.is_some() {
+                state.forgetting_rate *= config.student.failure_factor;
+            }
+            // Seeing the answer refreshes the memory either way.
+            state.last_review = Some(now);
+            report.reviews += 1;
+
+            training
+                .finalize_answer(Record {
+                    question,
+                    score: if recalled { 1.0 } else { 0.0 },
+                    time: now,
+                })
+                .expect("memory recorder can't fail");
+            clock.advance(review_duration);
+        }
+
+        // Retention at the end of the day, just before the next session.
+        let end_of_day =
+            start + std::time::Duration::from_secs_f64((day + 1) as f64 * SECONDS_PER_DAY);
+        let seen = memory
+            .iter()
+            .filter(|m| m.last_review.is_some())
+            .collect::<Vec<_>>();
+        if !seen.is_empty() {
+            report.retention =
+                seen.iter().map(|m| m.recall(end_of_day)).sum::<f64>() / seen.len() as f64;
+        }
+        days.push(report);
+    }
+
+    let total_reviews = days.iter().map(|d| d.reviews).sum::<usize>();
+    let day_count = days.len().max(1) as f64;
+    SimulationReport {
+        total_reviews,
+        reviews_per_day: total_reviews as f64 / day_count,
+        final_retention: days.last().map(|d| d.retention).unwrap_or(0.0),
+        average_retention: days.iter().map(|d| d.retention).sum::<f64>() / day_count,
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::super_memo_2::SuperMemo2Selector;
+    use crate::text::hex_learnables;
+
+    #[test]
+    fn test_simulate() {
+        let config = SimulationConfig {
+            days: 20,
+            ..Default::default()
+        };
+        let report = simulate(
+            hex_learnables(10),
+            Box::new(SuperMemo2Selector::new(Default::default())),
+            &config,
+        );
+        assert_eq!(report.days.len(), 20);
+        // All questions are introduced on the first day, failed and re-reviewed.
+        assert_eq!(report.days[0].new_questions, 10);
+        assert!(report.days[0].reviews >= 20);
+        assert!(report.final_retention > 0.0);
+
+        // Same inputs, same result.
+        let again = simulate(
+            hex_learnables(10),
+            Box::new(SuperMemo2Selector::new(Default::default())),
+            &config,
+        );
+        assert_eq!(report, again);
+    }
+}
@@ -172,22 +172,19 @@ pub fn load_text_learnables(
 
             for (r1, t, r2) in relations.iter() {
                 let repr1 = representations.get(r1).ok_or_else(|| {
-                    Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to find representation: {r1:?}"),
-                    ))
+                    Box::new(std::io::Error::other(format!(
+                        "Failed to find representation: {r1:?}"
+                    )))
                 })?;
                 let repr2 = representations.get(r2).ok_or_else(|| {
-                    Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to find representation: {r2:?}"),
-                    ))
+                    Box::new(std::io::Error::other(format!(
+                        "Failed to find representation: {r2:?}"
+                    )))
                 })?;
                 let tr = transforms.get(t).ok_or_else(|| {
-                    Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to find transform: {t:?}"),
-                    ))
+                    Box::new(std::io::Error::other(format!(
+                        "Failed to find transform: {t:?}"
+                    )))
                 })?;
                 edges.push((repr1.clone(), tr.clone(), repr2.clone()));
             }
@@ -198,8 +195,7 @@ pub fn load_text_learnables(
 
         return Ok(res);
     }
-    Err(Box::new(std::io::Error::new(
-        std::io::ErrorKind::Other,
+    Err(Box::new(std::io::Error::other(
         "File type not supported. Use .yaml.",
     )))
 }
@@ -255,3 +251,19 @@ pub fn save_text_learnables(
 
     Ok(())
 }
+
+/// Learnables for tests, each converts a hexadecimal number to decimal.
+#[cfg(test)]
+pub(crate) fn hex_learnables(count: Id) -> Vec<Box<dyn Learnable>> {
+    let transform = TextTransform::new("to decimal", TransformId(0));
+    (0..count)
+        .map(|i| {
+            let from = TextRepresentation::new(&format!("{i:#x}"), RepresentationId(2 * i));
+            let to = TextRepresentation::new(&format!("{i}"), RepresentationId(2 * i + 1));
+            Box::new(TextLearnable::new(
+                &[(from, transform.clone(), to)],
+                LearnableId(i),
+            )) as Box<dyn Learnable>
+        })
+        .collect()
+}
@@ -5,6 +5,7 @@ use crate::traits::*;
 /// - Propose answer
 /// - Rate answer
 /// - Submit answer
+///
 /// Also provides accessors for transforms and representations.
 pub struct Training {
     // learnables: Vec<Box<dyn Learnable>>,
@@ -14,16 +15,58 @@ pub struct Training {
     transforms: std::collections::HashMap<TransformId, std::sync::Arc<dyn Transform>>,
     representations:
         std::collections::HashMap<RepresentationId, std::sync::Arc<dyn Representation>>,
+    clock: std::sync::Arc<dyn Clock>,
+    /// Selector used in cram mode, answers are not recorded while this is set.
+    cram: Option<crate::algorithm::cram::CramSelector>,
+    /// Suspended and buried questions, these are not asked while active.
+    suspensions: Vec<Suspension>,
+    day_boundary: crate::clock::DayBoundary,
 }
 
 impl Training {
     /// Load the training object with a collection of learnables, a recorder and a selector.
-    /// This sets up the selector with the questions that can be asked from the learnables.
+    /// This sets up the selector with the questions that can be asked from the learnables. The
+    /// system clock is used for the current time, and passed to the selector. Fails if the
+    /// suspensions can't be retrieved from the recorder.
     pub fn new(
         learnables: Vec<Box<dyn Learnable>>,
         recorder: Box<dyn Recorder>,
         selector: Box<dyn Selector>,
-    ) -> Self {
+    ) -> Result<Self, MemorizerError> {
+        let clock = std::sync::Arc::new(crate::clock::SystemClock);
+        Self::build(learnables, recorder, selector, clock, Default::default())
+    }
+
+    /// Like [`Training::new`], with the provided day boundary instead of the default one. This
+    /// avoids setting up the selector twice, which [`Training::set_day_boundary`] would do.
+    pub fn with_day_boundary(
+        learnables: Vec<Box<dyn Learnable>>,
+        recorder: Box<dyn Recorder>,
+        selector: Box<dyn Selector>,
+        day_boundary: crate::clock::DayBoundary,
+    ) -> Result<Self, MemorizerError> {
+        let clock = std::sync::Arc::new(crate::clock::SystemClock);
+        Self::build(learnables, recorder, selector, clock, day_boundary)
+    }
+
+    /// Like [`Training::new`], with the provided clock instead of the system clock, for tests
+    /// and simulations. The selector is set up with this clock right away.
+    pub fn with_clock(
+        learnables: Vec<Box<dyn Learnable>>,
+        recorder: Box<dyn Recorder>,
+        selector: Box<dyn Selector>,
+        clock: std::sync::Arc<dyn Clock>,
+    ) -> Result<Self, MemorizerError> {
+        Self::build(learnables, recorder, selector, clock, Default::default())
+    }
+
+    fn build(
+        learnables: Vec<Box<dyn Learnable>>,
+        recorder: Box<dyn Recorder>,
+        selector: Box<dyn Selector>,
+        clock: std::sync::Arc<dyn Clock>,
+        day_boundary: crate::clock::DayBoundary,
+    ) -> Result<Self, MemorizerError> {
         let mut transforms: std::collections::HashMap<TransformId, std::sync::Arc<dyn Transform>> =
             Default::default();
         let mut representations: std::collections::HashMap<
@@ -43,32 +86,206 @@ impl Training {
 
         // let mut selector = Box::new(DummySelector::new());
         let mut selector = selector;
-        selector.set_questions(&questions, &*recorder);
-        Training {
+        selector.set_clock(clock.clone());
+        selector.set_day_boundary(day_boundary);
+        let suspensions = recorder.get_suspensions()?;
+        let mut training = Training {
             // learnables,
             questions,
-            recorder: recorder,
+            recorder,
             selector,
             transforms,
             representations,
-        }
+            clock,
+            cram: None,
+            suspensions,
+            day_boundary,
+        };
+        training.update_selector();
+        Ok(training)
     }
 
-    /// Update the selector with the current questions.
+    /// Update the selector with the current questions. This restores the snapshot in the
+    /// recorder if it is up to date, else the selector replays the records.
     fn update_selector(&mut self) {
+        if let Some(snapshot) = self.current_snapshot() {
+            if self
+                .selector
+                .restore(&self.questions, &*self.recorder, &snapshot.state)
+                .is_ok()
+            {
+                return;
+            }
+        }
         self.selector
             .set_questions(&self.questions, &*self.recorder);
     }
 
-    /// Set the new selector and pass the questions to it.
+    /// The snapshot in the recorder, if it was taken with the records currently stored.
+    fn current_snapshot(&self) -> Option<SelectorSnapshot> {
+        let snapshot = self.recorder.get_snapshot().ok()??;
+        let record_count = self.recorder.record_count().ok()?;
+        (snapshot.record_count == record_count).then_some(snapshot)
+    }
+
+    /// Store a snapshot of the selector state in the recorder, such that the next time the
+    /// selector is set up it doesn't need to replay all records. Does nothing if the selector
+    /// doesn't support snapshots.
+    pub fn store_snapshot(&mut self) -> Result<(), MemorizerError> {
+        let Some(state) = self.selector.snapshot() else {
+            return Ok(());
+        };
+        let snapshot = SelectorSnapshot {
+            record_count: self.recorder.record_count()?,
+            state,
+        };
+        self.recorder.store_snapshot(&snapshot)
+    }
+
+    /// Set the new selector and pass the clock, day boundary and questions to it.
     pub fn set_selector(&mut self, selector: Box<dyn Selector>) {
         self.selector = selector;
+        self.selector.set_clock(self.clock.clone());
+        self.selector.set_day_boundary(self.day_boundary);
         self.update_selector();
     }
 
+    /// Set where one day ends and the next starts, this passes it to the selector and sets up
+    /// the selector again.
+    pub fn set_day_boundary(&mut self, boundary: crate::clock::DayBoundary) {
+        self.day_boundary = boundary;
+        self.selector.set_day_boundary(boundary);
+        self.update_selector();
+    }
+
+    /// Set the clock used for the current time and pass it to the selector. The selector keeps
+    /// its state, use [`Training::with_clock`] to set it up with the clock from the start.
+    pub fn set_clock(&mut self, clock: std::sync::Arc<dyn Clock>) {
+        self.clock = clock;
+        self.selector.set_clock(self.clock.clone());
+    }
+
+    /// The current time according to the clock of this training.
+    pub fn now(&self) -> std::time::SystemTime {
+        self.clock.now()
+    }
+
     /// Obtain a new question, or if there's no more questions to ask an empty.
+    /// Suspended and buried questions are not asked.
     pub fn question(&mut self) -> Option<Question> {
-        self.selector.get_question()
+        let now = self.clock.now();
+        let suspensions = &self.suspensions;
+        let allowed = |q: &Question| {
+            !suspensions
+                .iter()
+                .any(|s| s.question == *q && s.is_active(now))
+        };
+        if let Some(cram) = self.cram.as_mut() {
+            return cram.get_question_filtered(&allowed);
+        }
+        self.selector.get_question_filtered(&allowed)
+    }
+
+    /// Suspend a question, it is not asked until it is unsuspended.
+    pub fn suspend(&mut self, question: &Question) -> Result<(), MemorizerError> {
+        self.store_suspension(Suspension {
+            question: *question,
+            until: None,
+        })
+    }
+
+    /// Bury a question, it is not asked until the next day.
+    pub fn bury(&mut self, question: &Question) -> Result<(), MemorizerError> {
+        let until = self.day_boundary.days_after(self.clock.now(), 1);
+        self.store_suspension(Suspension {
+            question: *question,
+            until: Some(until),
+        })
+    }
+
+    fn store_suspension(&mut self, suspension: Suspension) -> Result<(), MemorizerError> {
+        self.recorder.store_suspension(&suspension)?;
+        self.suspensions
+            .retain(|s| s.question != suspension.question);
+        self.suspensions.push(suspension);
+        Ok(())
+    }
+
+    /// Undo the suspension or burial of a question.
+    pub fn unsuspend(&mut self, question: &Question) -> Result<(), MemorizerError> {
+        self.recorder.remove_suspension(question)?;
+        self.suspensions.retain(|s| s.question != *question);
+        Ok(())
+    }
+
+    /// The questions that are currently suspended or buried.
+    pub fn suspended(&self) -> Vec<Question> {
+        let now = self.clock.now();
+        self.suspensions
+            .iter()
+            .filter(|s| s.is_active(now))
+            .map(|s| s.question)
+            .collect()
+    }
+
+    /// Enable or disable cram mode. In cram mode all questions are practiced until each is
+    /// answered well, regardless of whether they are due. Answers given in cram mode are not
+    /// stored in the recorder nor passed to the selector, such that its schedule is unaffected.
+    pub fn set_cram(&mut self, enabled: bool) {
+        if enabled {
+            let mut cram = crate::algorithm::cram::CramSelector::new();
+            cram.set_questions(&self.questions, &*self.recorder);
+            self.cram = Some(cram);
+        } else {
+            self.cram = None;
+        }
+    }
+
+    /// Whether cram mode is enabled.
+    pub fn is_cram(&self) -> bool {
+        self.cram.is_some()
+    }
+
+    /// Estimated probability of recalling the answer to a question right now, if the selector
+    /// provides such an estimate.
+    pub fn predict_recall(&self, question: &Question) -> Option<f64> {
+        self.selector.predict_recall(question, self.clock.now())
+    }
+
+    /// Number of questions that become due on each of the next days, if the selector can
+    /// forecast this. See [`Selector::forecast`].
+    pub fn forecast(&self, days: usize) -> Option<Vec<usize>> {
+        self.selector.forecast(days)
+    }
+
+    /// Number of questions in each stage of learning, if the selector tracks this.
+    pub fn progress(&self) -> Option<Progress> {
+        self.selector.progress()
+    }
+
+    /// Why the selector chose the last question, if it can explain this. Cramming doesn't use the
+    /// selector, so there is nothing to explain then. See [`Selector::explain`].
+    pub fn explain(&self) -> Option<Explanation> {
+        if self.is_cram() {
+            return None;
+        }
+        self.selector.explain()
+    }
+
+    /// Questions that were failed so often that they are leeches, these are likely to be badly
+    /// phrased and worth rewriting. Determined from the records in the recorder.
+    pub fn leeches(
+        &self,
+        config: &crate::algorithm::leech::LeechConfig,
+    ) -> Result<Vec<Question>, MemorizerError> {
+        let mut records = crate::algorithm::history(&*self.recorder, &self.questions);
+        records.sort_by_key(|r| r.time);
+        Ok(crate::algorithm::leech::find_leeches(
+            &self.questions,
+            &records,
+            config,
+            &self.day_boundary,
+        ))
     }
 
     /// Obtain a question from a learnable id.
@@ -79,6 +296,15 @@ impl Training {
             .map(|i| self.questions[i])
     }
 
+    /// Obtain all questions of a learnable, one for each of its directions.
+    pub fn questions_from_learnable(&self, learnable: LearnableId) -> Vec<Question> {
+        self.questions
+            .iter()
+            .filter(|a| a.learnable == learnable)
+            .copied()
+            .collect()
+    }
+
     /// Obtain the representation by id.
     pub fn representation(&self, id: RepresentationId) -> std::sync::Arc<dyn Representation> {
         self.representations
@@ -103,7 +329,7 @@ impl Training {
         self.representations
             .get(&question.to)
             .cloned()
-            .ok_or(format!("could not find question").into())
+            .ok_or("could not find question".into())
     }
 
     /// Check if a particular question is present.
@@ -121,9 +347,9 @@ impl Training {
         let representation = self
             .representations
             .get(&question.to)
-            .ok_or(format!("could not find representation for this answer"))?;
+            .ok_or("could not find representation for this answer")?;
         let score = representation.get_similarity(&*given_answer);
-        let time = std::time::SystemTime::now();
+        let time = self.clock.now();
         let record = Record {
             question: *question,
             score,
@@ -132,12 +358,184 @@ impl Training {
         Ok((record, representation.clone()))
     }
 
-    /// Finalize the record, storing it in the recorder and selector.
+    /// Finalize the record, storing it in the recorder and selector. In cram mode the record is
+    /// only used to determine the questions still to practice.
     pub fn finalize_answer(&mut self, record: Record) -> Result<(), MemorizerError> {
         assert!(record.score >= 0.0);
         assert!(record.score <= 1.0);
+        if let Some(cram) = self.cram.as_mut() {
+            cram.store_record(&record);
+            return Ok(());
+        }
         self.recorder.store_record(&record)?;
         self.selector.store_record(&record);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::super_memo_2::SuperMemo2Selector;
+    use crate::recorder::MemoryRecorder;
+    use crate::text::hex_learnables;
+
+    #[test]
+    fn test_cram() {
+        let learnables = hex_learnables(3);
+        let mut training = Training::new(
+            learnables,
+            Box::new(MemoryRecorder::new()),
+            Box::new(SuperMemo2Selector::default()),
+        )
+        .unwrap();
+
+        // Cram through all questions, failing each one once.
+        training.set_cram(true);
+        let mut asked = 0;
+        while let Some(question) = training.question() {
+            let score = if asked < 3 { 0.0 } else { 1.0 };
+            let time = training.now();
+            training
+                .finalize_answer(Record {
+                    question,
+                    score,
+                    time,
+                })
+                .unwrap();
+            asked += 1;
+        }
+        assert_eq!(asked, 6);
+
+        // The selector never saw these answers.
+        training.set_cram(false);
+        assert_eq!(training.progress().map(|p| p.new), Some(3));
+    }
+
+    #[test]
+    fn test_suspend() {
+        let learnables = hex_learnables(2);
+        let clock = crate::algorithm::test_clock();
+        let mut training = Training::with_clock(
+            learnables,
+            Box::new(MemoryRecorder::new()),
+            Box::new(crate::algorithm::dummy::DummySelector::new()),
+            clock.clone(),
+        )
+        .unwrap();
+        let first = training.question_from_learnable(LearnableId(0)).unwrap();
+        let second = training.question_from_learnable(LearnableId(1)).unwrap();
+
+        training.suspend(&first).unwrap();
+        training.bury(&second).unwrap();
+        assert_eq!(training.question(), None);
+
+        // The burial expires the next day, the suspension remains.
+        clock.advance(std::time::Duration::from_secs(24 * 60 * 60));
+        assert_eq!(training.suspended(), vec![first]);
+        assert_eq!(training.question(), Some(second));
+
+        training.unsuspend(&first).unwrap();
+        assert!(training.suspended().is_empty());
+    }
+
+    #[test]
+    fn test_questions_from_learnable() {
+        use crate::text::{TextLearnable, TextRepresentation, TextTransform};
+        let hex = TextRepresentation::new("0xa", RepresentationId(0));
+        let dec = TextRepresentation::new("10", RepresentationId(1));
+        let learnable = TextLearnable::new(
+            &[
+                (
+                    hex.clone(),
+                    TextTransform::new("to decimal", TransformId(0)),
+                    dec.clone(),
+                ),
+                (dec, TextTransform::new("to hex", TransformId(1)), hex),
+            ],
+            LearnableId(0),
+        );
+        let mut learnables = hex_learnables(2);
+        learnables[0] = Box::new(learnable);
+        let training = Training::new(
+            learnables,
+            Box::new(MemoryRecorder::new()),
+            Box::new(crate::algorithm::dummy::DummySelector::new()),
+        )
+        .unwrap();
+
+        // Both directions of the first learnable, none of the other.
+        let questions = training.questions_from_learnable(LearnableId(0));
+        assert_eq!(questions.len(), 2);
+        assert!(questions.iter().all(|q| q.learnable == LearnableId(0)));
+        assert_ne!(questions[0], questions[1]);
+        assert_eq!(training.questions_from_learnable(LearnableId(2)), vec![]);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let learnables = hex_learnables(1);
+        let mut training = Training::new(
+            learnables,
+            Box::new(MemoryRecorder::new()),
+            Box::new(SuperMemo2Selector::default()),
+        )
+        .unwrap();
+
+        // Failing the question marks it for re-review, this isn't derived from the records.
+        let question = training.question().unwrap();
+        let time = training.now();
+        training
+            .finalize_answer(Record {
+                question,
+                score: 0.0,
+                time,
+            })
+            .unwrap();
+        training.store_snapshot().unwrap();
+        training.set_selector(Box::new(SuperMemo2Selector::default()));
+        assert_eq!(training.progress().map(|p| p.re_review), Some(1));
+
+        // Another answer makes the snapshot stale, the selector is set up from the records.
+        training
+            .finalize_answer(Record {
+                question,
+                score: 0.0,
+                time,
+            })
+            .unwrap();
+        training.set_selector(Box::new(SuperMemo2Selector::default()));
+        assert_eq!(training.progress().map(|p| p.re_review), Some(0));
+    }
+
+    /// A recorder whose suspensions can't be read, like a corrupt file.
+    #[derive(Debug)]
+    struct BrokenRecorder;
+
+    impl Recorder for BrokenRecorder {
+        fn store_record(&mut self, _record: &Record) -> Result<(), MemorizerError> {
+            Ok(())
+        }
+
+        fn get_records_by_question(
+            &self,
+            _question: &Question,
+        ) -> Result<Vec<Record>, MemorizerError> {
+            Ok(vec![])
+        }
+
+        fn get_suspensions(&self) -> Result<Vec<Suspension>, MemorizerError> {
+            Err("unreadable suspensions".into())
+        }
+    }
+
+    #[test]
+    fn test_suspensions_error() {
+        let training = Training::new(
+            hex_learnables(1),
+            Box::new(BrokenRecorder),
+            Box::new(SuperMemo2Selector::default()),
+        );
+        assert!(training.is_err());
+    }
+}
@@ -74,7 +74,7 @@ pub trait Transform: std::fmt::Debug + Send + Sync {
 }
 
 /// A struct representing a particular question.
-#[derive(Debug, PartialEq, Copy, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Eq, Hash, PartialEq, Copy, Clone, Deserialize, Serialize, Default)]
 pub struct Question {
     /// From which learnable this question originates.
     pub learnable: LearnableId,
@@ -121,6 +121,23 @@ pub struct Record {
     pub time: std::time::SystemTime,
 }
 
+/// A question held back from being asked, by suspending it or burying it until a later time.
+#[derive(Debug, PartialEq, Copy, Clone, Deserialize, Serialize)]
+pub struct Suspension {
+    /// The question that is held back.
+    pub question: Question,
+
+    /// Until when the question is held back, suspended until undone if None.
+    pub until: Option<std::time::SystemTime>,
+}
+
+impl Suspension {
+    /// Whether the question is still held back at the provided time.
+    pub fn is_active(&self, now: std::time::SystemTime) -> bool {
+        self.until.map(|until| now < until).unwrap_or(true)
+    }
+}
+
 /// Something to track past performance.
 pub trait Recorder: std::fmt::Debug + Send + Sync {
     /// Store an answer.
@@ -128,6 +145,91 @@ pub trait Recorder: std::fmt::Debug + Send + Sync {
 
     /// Retrieve records for a particular question.
     fn get_records_by_question(&self, question: &Question) -> Result<Vec<Record>, MemorizerError>;
+
+    /// Retrieve all records, in the order they were stored. Selectors fall back to the records
+    /// of their questions if this isn't supported.
+    fn get_records(&self) -> Result<Vec<Record>, MemorizerError> {
+        Err("this recorder doesn't support retrieving all records".into())
+    }
+
+    /// Store a suspension, this replaces an earlier suspension of the same question.
+    fn store_suspension(&mut self, _suspension: &Suspension) -> Result<(), MemorizerError> {
+        Err("this recorder doesn't support suspensions".into())
+    }
+
+    /// Remove the suspension of a question, if any.
+    fn remove_suspension(&mut self, _question: &Question) -> Result<(), MemorizerError> {
+        Err("this recorder doesn't support suspensions".into())
+    }
+
+    /// Retrieve all suspensions, including those that expired.
+    fn get_suspensions(&self) -> Result<Vec<Suspension>, MemorizerError> {
+        Ok(vec![])
+    }
+
+    /// Number of records stored.
+    fn record_count(&self) -> Result<usize, MemorizerError> {
+        Ok(self.get_records()?.len())
+    }
+
+    /// Store a snapshot of the selector state, this replaces the earlier snapshot. Recorders
+    /// that don't support snapshots ignore it, the selector then replays the records.
+    fn store_snapshot(&mut self, _snapshot: &SelectorSnapshot) -> Result<(), MemorizerError> {
+        Ok(())
+    }
+
+    /// Retrieve the stored snapshot of the selector state, if any.
+    fn get_snapshot(&self) -> Result<Option<SelectorSnapshot>, MemorizerError> {
+        Ok(None)
+    }
+}
+
+/// Serialized state of a selector, this allows setting up the selector without replaying all
+/// records.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SelectorSnapshot {
+    /// Number of records the state was derived from, the snapshot is stale if the recorder
+    /// holds a different number of records.
+    pub record_count: usize,
+    /// The state as provided by [`Selector::snapshot`].
+    pub state: serde_yaml::Value,
+}
+
+/// Number of questions in each stage of learning, for progress displays.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Progress {
+    /// Questions that were answered before and are due for review now.
+    pub due: usize,
+    /// Questions answered poorly this session that will be asked again, not counting those due.
+    pub re_review: usize,
+    /// Questions that were never answered.
+    pub new: usize,
+    /// Questions with a long interval between reviews, these are considered learned.
+    pub mature: usize,
+    /// Total number of questions.
+    pub total: usize,
+}
+
+/// Reasoning of a selector for choosing a question, to find out why a question is asked.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Explanation {
+    /// The question that was chosen.
+    pub question: Question,
+    /// The group of questions it was picked from, for example "due" or "re-review".
+    pub pool: String,
+    /// Seconds since the question became due, negative if it is not due yet. None if the
+    /// question has no due time.
+    pub overdue_seconds: Option<f64>,
+    /// Estimated probability of recall when it was chosen, if the selector has one.
+    pub recall: Option<f64>,
+    /// Selector specific state of the question, as names and values.
+    pub state: Vec<(String, String)>,
+}
+
+/// Source of the current time, allows controlling time in tests and simulations.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// The current time.
+    fn now(&self) -> std::time::SystemTime;
 }
 
 /// The entity that decided what questions to ask. Only works on Ids.
@@ -138,6 +240,100 @@ pub trait Selector: std::fmt::Debug + Send + Sync {
     /// Retrieve a question to ask, if empty session is done, no questions to ask right now.
     fn get_question(&mut self) -> Option<Question>;
 
+    /// Retrieve a question to ask from the questions for which allowed returns true, this allows
+    /// wrapping selectors to hold back questions. Empty if none of the allowed questions is to be
+    /// asked right now. By default this asks get_question and returns nothing if that question
+    /// isn't allowed, selectors should provide this to pick another question instead.
+    fn get_question_filtered(&mut self, allowed: &dyn Fn(&Question) -> bool) -> Option<Question> {
+        self.get_question().filter(|q| allowed(q))
+    }
+
     /// Store answer to a question, not guaranteed to be in sync with get_question.
     fn store_record(&mut self, record: &Record);
+
+    /// Partial credit for a question from a good answer to a related question, the score of the
+    /// record is the credit. This is not an answer to the question; selectors may postpone it a
+    /// bit or let the first answer to a new question count for more, but it shouldn't count as
+    /// a review. Ignored by default.
+    fn store_partial_credit(&mut self, _record: &Record) {}
+
+    /// Like set_questions, with the partial credit that was given over time in order of time.
+    /// Selectors for which credit changes how a later answer is handled should replay it along
+    /// with the records. By default the questions are set, after which the credit given since
+    /// the last answer to each question is stored.
+    fn set_questions_with_credit(
+        &mut self,
+        questions: &[Question],
+        recorder: &dyn Recorder,
+        credit: &[Record],
+    ) {
+        self.set_questions(questions, recorder);
+        let mut last_answer = std::collections::HashMap::new();
+        for record in crate::algorithm::history(recorder, questions) {
+            let time = last_answer.entry(record.question).or_insert(record.time);
+            *time = record.time.max(*time);
+        }
+        for record in credit.iter() {
+            if last_answer
+                .get(&record.question)
+                .map(|t| *t < record.time)
+                .unwrap_or(true)
+            {
+                self.store_partial_credit(record);
+            }
+        }
+    }
+
+    /// Set the clock this selector uses to determine the current time. Should be called before
+    /// set_questions.
+    fn set_clock(&mut self, _clock: std::sync::Arc<dyn Clock>) {}
+
+    /// Set the random number generator this selector uses, a seeded generator makes the order
+    /// of the questions reproducible.
+    fn set_rng(&mut self, _rng: crate::rng::SelectorRng) {}
+
+    /// Set where one day ends and the next starts, used by selectors with intervals in days.
+    /// Should be called before set_questions.
+    fn set_day_boundary(&mut self, _boundary: crate::clock::DayBoundary) {}
+
+    /// Estimated probability of recalling the answer to a question at the provided time, None if
+    /// the selector has no estimate for it.
+    fn predict_recall(&self, _question: &Question, _at_time: std::time::SystemTime) -> Option<f64> {
+        None
+    }
+
+    /// Forecast of the workload; the number of questions that become due on each of the next
+    /// days. The first entry holds the questions due right now, entry i those that become due
+    /// within i days from now but not earlier. None if the selector can't forecast.
+    fn forecast(&self, _days: usize) -> Option<Vec<usize>> {
+        None
+    }
+
+    /// Number of questions in each stage of learning, None if the selector doesn't track this.
+    fn progress(&self) -> Option<Progress> {
+        None
+    }
+
+    /// Why the last question was chosen, None if the selector can't explain it.
+    fn explain(&self) -> Option<Explanation> {
+        None
+    }
+
+    /// The state of this selector, such that it can be restored without replaying the records.
+    /// None if the selector doesn't support this.
+    fn snapshot(&self) -> Option<serde_yaml::Value> {
+        None
+    }
+
+    /// Set up the selector from a state provided by snapshot, instead of set_questions. Fails if
+    /// the state isn't for this selector or these questions, the selector should then be set up
+    /// with set_questions.
+    fn restore(
+        &mut self,
+        _questions: &[Question],
+        _recorder: &dyn Recorder,
+        _state: &serde_yaml::Value,
+    ) -> Result<(), MemorizerError> {
+        Err("this selector doesn't support snapshots".into())
+    }
 }